@@ -2,40 +2,393 @@
 #include <stdio.h> // for printing to stderr
 #include <time.h>
 #include <math.h>
+#include <stdatomic.h>
+#include <pthread.h>
 #include "mtwister.h"
 
+#define DE_MAX_DONORS 5U
+#define DE_PI 3.14159265358979323846
+#define DE_JDE_F_LOWER 0.1 // jDE regenerates F uniformly in [F_l; F_l + F_u)
+#define DE_JDE_F_RANGE 0.9
+#define DE_ENSEMBLE_SUCCESS_RATE_FLOOR 0.01 // SaDE's epsilon, keeps every pooled strategy selectable
+#define DE_SADE_F_MEAN 0.5
+#define DE_SADE_F_DEVIATION 0.3
 
 typedef struct {
     vectorBuf_t members; /** @optimize memory */
-    uint64_t size;
+    double* fitness; // cached objective value of every member, kept in sync whenever a member changes
+    double* violation; // cached total constraint violation of every member, likewise
+    double* f; double* cr; // F and CR of every member (jDE), for the probes the values they were created with
+    de_mutation_strategy_t* strategy; // mutation strategy of every member (EPSDE), for the probes the one they were created with
+    uint64_t size; // live members, may shrink below `capacity` during the run
+    uint64_t capacity; // allocated members, all of them own coordinate buffers
 } de_population_t;
 
 typedef de_population_t* pDePopulation_t;
 
-void de_population_allocate_members (pDePopulation_t pPop) { pPop->members = malloc(pPop->size * sizeof(vector_t)); }
-void de_population_free_members (pDePopulation_t pPop) { free(pPop->members); }
+void de_population_allocate_members (pDePopulation_t pPop) {
+    pPop->capacity = pPop->size;
+    pPop->members = malloc(pPop->size * sizeof(vector_t));
+    pPop->fitness = malloc(pPop->size * sizeof(double));
+    pPop->violation = calloc(pPop->size, sizeof(double));
+    pPop->f = malloc(pPop->size * sizeof(double));
+    pPop->cr = malloc(pPop->size * sizeof(double));
+    pPop->strategy = malloc(pPop->size * sizeof(de_mutation_strategy_t));
+}
+void de_population_free_members (pDePopulation_t pPop) { free(pPop->members); free(pPop->fitness); free(pPop->violation); free(pPop->f); free(pPop->cr); free(pPop->strategy); }
+
+/**
+ * Exchange members `a` and `b` together with their cached values, the coordinate buffers move along
+*/
+void de_population_swap_members (pDePopulation_t pPop, uint64_t a, uint64_t b) {
+    vector_t member = pPop->members[a]; pPop->members[a] = pPop->members[b]; pPop->members[b] = member;
+    double fitness = pPop->fitness[a]; pPop->fitness[a] = pPop->fitness[b]; pPop->fitness[b] = fitness;
+    double violation = pPop->violation[a]; pPop->violation[a] = pPop->violation[b]; pPop->violation[b] = violation;
+    double f = pPop->f[a]; pPop->f[a] = pPop->f[b]; pPop->f[b] = f;
+    double cr = pPop->cr[a]; pPop->cr[a] = pPop->cr[b]; pPop->cr[b] = cr;
+    de_mutation_strategy_t strategy = pPop->strategy[a]; pPop->strategy[a] = pPop->strategy[b]; pPop->strategy[b] = strategy;
+}
+
+typedef struct {
+    pDeOptimizationTarget_t pTarget;
+    pDePopulation_t pPopulation;
+    uint32_t first; uint32_t count;
+    const double* pBatchCoordinates; // the rows of this chunk, only for `f_batch`
+    double equalityTolerance;
+    void* pUserData;
+} de_evaluation_chunk_t;
+
+/**
+ * @returns sum max(0, g_k) + sum max(0, |h_k| - tolerance) over the constraint values written by `pTarget->g`
+*/
+double de_total_violation(pDeOptimizationTarget_t pTarget, const double* pValues, double equalityTolerance) {
+    double violation = 0.0;
+    for (uint32_t k = 0; k < pTarget->num_inequality_constraints; k++) {
+        violation += fmax(0.0, pValues[k]);
+    }
+    for (uint32_t k = 0; k < pTarget->num_equality_constraints; k++) {
+        violation += fmax(0.0, fabs(pValues[pTarget->num_inequality_constraints + k]) - equalityTolerance);
+    }
+    return isnan(violation) ? INFINITY : violation;
+}
+
+void* de_evaluate_chunk(void* pArg) {
+    de_evaluation_chunk_t* pChunk = pArg;
+    pDeOptimizationTarget_t pTarget = pChunk->pTarget;
+    if (pTarget->f_batch != NULL) {
+        pTarget->f_batch(pChunk->pBatchCoordinates, pChunk->count, pTarget->num_dimensions, &pChunk->pPopulation->fitness[pChunk->first], pChunk->pUserData);
+    } else {
+        for (uint32_t i = pChunk->first; i < pChunk->first + pChunk->count; i++) {
+            pChunk->pPopulation->fitness[i] = pTarget->f(pChunk->pPopulation->members[i], pChunk->pUserData);
+        }
+    }
+    if (pTarget->g == NULL) {
+        return NULL;
+    }
+    double* constraintValues = malloc((pTarget->num_inequality_constraints + pTarget->num_equality_constraints + 1U) * sizeof(double));
+    for (uint32_t i = pChunk->first; i < pChunk->first + pChunk->count; i++) {
+        pTarget->g(pChunk->pPopulation->members[i], constraintValues, pChunk->pUserData);
+        pChunk->pPopulation->violation[i] = de_total_violation(pTarget, constraintValues, pChunk->equalityTolerance);
+    }
+    free(constraintValues);
+    return NULL;
+}
+
+typedef void* (*de_task_t)(void*);
+
+typedef struct {
+    struct de_worker_pool_s* pPool;
+    uint32_t index; // runs task index + 1 of every dispatch
+} de_worker_t;
+
+typedef struct de_worker_pool_s {
+    pthread_mutex_t mutex;
+    pthread_cond_t work_ready; pthread_cond_t work_done;
+    pthread_t* threads; de_worker_t* workers; uint32_t thread_count; // helper threads, the calling thread runs task 0 itself
+    de_task_t task; char* tasks; size_t task_size; uint32_t task_count; // the current dispatch
+    uint64_t generation; uint32_t pending; uint8_t is_stopping; // dispatch counter, helpers still busy with the current dispatch
+} de_worker_pool_t;
+
+void* de_worker_loop(void* pArg) {
+    de_worker_t* pWorker = pArg;
+    de_worker_pool_t* pPool = pWorker->pPool;
+    uint64_t seenGeneration = 0U;
+    pthread_mutex_lock(&pPool->mutex);
+    for (;;) {
+        while (pPool->generation == seenGeneration && !pPool->is_stopping) {
+            pthread_cond_wait(&pPool->work_ready, &pPool->mutex);
+        }
+        if (pPool->is_stopping) {
+            break;
+        }
+        seenGeneration = pPool->generation;
+        uint32_t taskIndex = pWorker->index + 1U;
+        if (taskIndex < pPool->task_count) {
+            pthread_mutex_unlock(&pPool->mutex);
+            pPool->task(pPool->tasks + taskIndex * pPool->task_size);
+            pthread_mutex_lock(&pPool->mutex);
+        }
+        if (--pPool->pending == 0U) {
+            pthread_cond_signal(&pPool->work_done);
+        }
+    }
+    pthread_mutex_unlock(&pPool->mutex);
+    return NULL;
+}
+
+void de_worker_pool_free(de_worker_pool_t* pPool) {
+    if (pPool == NULL) {
+        return;
+    }
+    pthread_mutex_lock(&pPool->mutex);
+    pPool->is_stopping = 1U;
+    pthread_cond_broadcast(&pPool->work_ready);
+    pthread_mutex_unlock(&pPool->mutex);
+    for (uint32_t t = 0; t < pPool->thread_count; t++) {
+        pthread_join(pPool->threads[t], NULL);
+    }
+    pthread_cond_destroy(&pPool->work_done);
+    pthread_cond_destroy(&pPool->work_ready);
+    pthread_mutex_destroy(&pPool->mutex);
+    free(pPool->workers);
+    free(pPool->threads);
+    free(pPool);
+}
+
+/**
+ * Start up to `threadCount` - 1 helper threads that wait for work until `de_worker_pool_free`
+ * @returns NULL if no helper thread could be started, `de_worker_pool_run` then runs everything on the calling thread
+*/
+de_worker_pool_t* de_worker_pool_create(uint32_t threadCount) {
+    de_worker_pool_t* pPool = malloc(sizeof(de_worker_pool_t));
+    pthread_mutex_init(&pPool->mutex, NULL);
+    pthread_cond_init(&pPool->work_ready, NULL);
+    pthread_cond_init(&pPool->work_done, NULL);
+    pPool->threads = malloc((threadCount - 1U) * sizeof(pthread_t));
+    pPool->workers = malloc((threadCount - 1U) * sizeof(de_worker_t));
+    pPool->thread_count = 0U;
+    pPool->task_count = 0U;
+    pPool->generation = 0U;
+    pPool->pending = 0U;
+    pPool->is_stopping = 0U;
+    for (uint32_t t = 0; t + 1U < threadCount; t++) {
+        pPool->workers[t] = (de_worker_t){ .pPool = pPool, .index = t };
+        if (pthread_create(&pPool->threads[t], NULL, de_worker_loop, &pPool->workers[t]) != 0) {
+            break; // out of threads, the pool runs with the ones it got
+        }
+        pPool->thread_count++;
+    }
+    if (pPool->thread_count == 0U) {
+        de_worker_pool_free(pPool);
+        return NULL;
+    }
+    return pPool;
+}
+
+/**
+ * Run `task` on each of the `taskCount` elements of `tasks` and wait for all of them, task t > 0 runs on helper t - 1,
+ * the calling thread takes task 0 and the tasks beyond the helpers (all of them without a pool)
+*/
+void de_worker_pool_run(de_worker_pool_t* pPool, de_task_t task, void* tasks, size_t taskSize, uint32_t taskCount) {
+    uint32_t helperCount = pPool != NULL ? pPool->thread_count : 0U;
+    if (helperCount > 0U && taskCount > 1U) {
+        pthread_mutex_lock(&pPool->mutex);
+        pPool->task = task;
+        pPool->tasks = tasks;
+        pPool->task_size = taskSize;
+        pPool->task_count = taskCount;
+        pPool->pending = helperCount;
+        pPool->generation++;
+        pthread_cond_broadcast(&pPool->work_ready);
+        pthread_mutex_unlock(&pPool->mutex);
+    }
+    for (uint32_t t = 0; t < taskCount; t++) {
+        if (t == 0U || t > helperCount) {
+            task((char*)tasks + t * taskSize);
+        }
+    }
+    if (helperCount > 0U && taskCount > 1U) {
+        pthread_mutex_lock(&pPool->mutex);
+        while (pPool->pending > 0U) {
+            pthread_cond_wait(&pPool->work_done, &pPool->mutex);
+        }
+        pthread_mutex_unlock(&pPool->mutex);
+    }
+}
 
 typedef struct {
     de_population_t main_population;
     de_population_t probe_population;
-    pVector_t p_main_best; double p_main_best_val;
+    pVector_t p_main_best; double p_main_best_val; double p_main_best_violation;
+    double epsilon_level; double epsilon_initial; // DE_CONSTRAINTS_EPSILON, the current level and v_0
+    uint32_t* donors; // DE_MAX_DONORS random member indices per probe, drawn in de_reproduce, consumed in de_mutate
+    uint32_t* ranking; // main population indices sorted by fitness, only maintained for DE/current-to-pbest/1
+    double mean_f; double mean_cr; // JADE
+    double* memory_f; double* memory_cr; uint32_t memory_size; uint32_t memory_index; // SHADE
+    double* success_f; double* success_cr; double* success_weight; uint32_t success_count; // parameters of the probes that replaced their parent this generation
+    double strategy_probability[DE_MUTATION_STRATEGY_COUNT]; double strategy_mean_cr[DE_MUTATION_STRATEGY_COUNT]; // SaDE/EPSDE, zero probability outside the pool
+    uint32_t* window_successes; uint32_t* window_failures; double* window_success_cr; // SaDE/EPSDE, learning_period rows of DE_MUTATION_STRATEGY_COUNT per-generation counts
+    uint32_t window_index; uint32_t window_fill; // row of the current generation, rows filled so far
+    uint64_t strategy_trials[DE_MUTATION_STRATEGY_COUNT]; uint64_t strategy_successes[DE_MUTATION_STRATEGY_COUNT]; // usage statistics of the whole run
+    de_population_t archive; uint32_t archive_capacity; // replaced parents, `archive.size` is the current fill and `archive_capacity` the current limit
+    uint64_t evaluation_count;
+    double stagnation_best_val; double stagnation_best_violation; uint64_t last_improvement_iter; // for STOP_ON_STAGNATION
+    struct timespec start_time; // for STOP_AFTER_SECONDS
+    de_generation_stats_t* history; uint64_t history_length; uint64_t history_capacity;
+    double* batch_coordinates; // row-major copy of the members handed to `f_batch`, allocated on first use
+    de_worker_pool_t* worker_pool; // helper threads of `thread_count` > 1, NULL evaluates on the calling thread
+    de_evaluation_chunk_t* evaluation_chunks; // one per pool thread including the calling one
     MTRand mt_rand;
 } de_workspace_t;
 
 typedef de_workspace_t* pDeWorkspace_t;
 
-void de_workspace_init (pDeWorkspace_t pWorkspace, uint32_t populationSize, uint32_t numDimensions) {
-    pWorkspace->mt_rand = seedRand(time(0));
+uint8_t de_is_ensemble(pDeConfig_t pConfig) {
+    return pConfig->adaptation == DE_ADAPTATION_SADE || pConfig->adaptation == DE_ADAPTATION_EPSDE;
+}
+
+/**
+ * @returns The `DE_STRATEGY_BIT`s of the strategies the run may use: the pool of an ensemble, otherwise just `mutation_strategy`
+*/
+uint32_t de_strategy_pool(pDeConfig_t pConfig) {
+    if (!de_is_ensemble(pConfig)) {
+        return DE_STRATEGY_BIT(pConfig->mutation_strategy);
+    }
+    return pConfig->strategy_pool != 0U ? pConfig->strategy_pool : DE_DEFAULT_STRATEGY_POOL;
+}
+
+/**
+ * Draw a strategy of the pool by the learned selection probabilities
+*/
+de_mutation_strategy_t de_draw_strategy(pDeWorkspace_t pWorkspace) {
+    double r = fmod(genRand(&pWorkspace->mt_rand), 1.0);
+    uint32_t last = 0U;
+    for (uint32_t k = 0; k < DE_MUTATION_STRATEGY_COUNT; k++) {
+        if (pWorkspace->strategy_probability[k] <= 0.0) {
+            continue;
+        }
+        last = k;
+        r -= pWorkspace->strategy_probability[k];
+        if (r < 0.0) {
+            break;
+        }
+    }
+    return (de_mutation_strategy_t)last; // also catches rounding at the end of the wheel
+}
+
+/**
+ * Give EPSDE member `i` a fresh strategy and (F, CR) pair from the pools
+*/
+void de_redraw_epsde_parameters(pDeWorkspace_t pWorkspace, uint32_t i) {
+    pDePopulation_t pMain = &pWorkspace->main_population;
+    pMain->strategy[i] = de_draw_strategy(pWorkspace);
+    pMain->f[i] = 0.4 + 0.1 * (double)(genRandLong(&pWorkspace->mt_rand) % 6U);
+    pMain->cr[i] = 0.1 + 0.1 * (double)(genRandLong(&pWorkspace->mt_rand) % 9U);
+}
+
+uint64_t de_seed_for_stream(uint64_t baseSeed, uint64_t streamIndex) {
+    // splitmix64 finalizer over the combined input
+    uint64_t z = baseSeed + (streamIndex + 1U) * 0x9E3779B97F4A7C15ULL;
+    z = (z ^ (z >> 30)) * 0xBF58476D1CE4E5B9ULL;
+    z = (z ^ (z >> 27)) * 0x94D049BB133111EBULL;
+    z = z ^ (z >> 31);
+    return z != 0U ? z : 1U;
+}
+
+/**
+ * Replace the worker pool of the workspace by one with `threadCount` threads (none for 0 or 1)
+*/
+void de_workspace_start_workers(pDeWorkspace_t pWorkspace, uint32_t threadCount) {
+    de_worker_pool_free(pWorkspace->worker_pool);
+    free(pWorkspace->evaluation_chunks);
+    pWorkspace->worker_pool = threadCount > 1U ? de_worker_pool_create(threadCount) : NULL;
+    pWorkspace->evaluation_chunks = pWorkspace->worker_pool != NULL ? malloc((pWorkspace->worker_pool->thread_count + 1U) * sizeof(de_evaluation_chunk_t)) : NULL;
+}
+
+void de_workspace_init (pDeWorkspace_t pWorkspace, pDeConfig_t pConfig, uint32_t numDimensions) {
+    uint32_t populationSize = pConfig->population_size;
+    uint64_t seed = pConfig->seed;
+    if (seed == 0U) {
+        // Runs starting within the same second must not share a stream, so mix in the (per-thread) workspace address and a run counter
+        static atomic_uint_fast64_t unseededRunCount = 0U;
+        uint64_t runIndex = (uint64_t)atomic_fetch_add(&unseededRunCount, 1U);
+        seed = de_seed_for_stream((uint64_t)time(0) ^ (uint64_t)(uintptr_t)pWorkspace, runIndex);
+    }
+    // mtwister only keeps the low 32 bits and degenerates to a constant stream for a zero seed
+    unsigned long mtSeed = (unsigned long)((seed ^ (seed >> 32)) & 0xffffffffULL);
+    pWorkspace->mt_rand = seedRand(mtSeed != 0U ? mtSeed : 4357U);
 
     pWorkspace->main_population.size = populationSize;
     pWorkspace->probe_population.size = populationSize;
     de_population_allocate_members (&pWorkspace->main_population);
     de_population_allocate_members (&pWorkspace->probe_population);
+    pWorkspace->donors = malloc(populationSize * DE_MAX_DONORS * sizeof(uint32_t));
+    pWorkspace->ranking = malloc(populationSize * sizeof(uint32_t));
+    pWorkspace->mean_f = pConfig->amplification_factor;
+    pWorkspace->mean_cr = pConfig->crossover_probability;
+    pWorkspace->memory_size = pConfig->adaptation == DE_ADAPTATION_SHADE ? pConfig->history_size : 1U;
+    pWorkspace->memory_index = 0U;
+    pWorkspace->memory_f = malloc(pWorkspace->memory_size * sizeof(double));
+    pWorkspace->memory_cr = malloc(pWorkspace->memory_size * sizeof(double));
+    for (uint32_t k = 0; k < pWorkspace->memory_size; k++) {
+        pWorkspace->memory_f[k] = pConfig->amplification_factor;
+        pWorkspace->memory_cr[k] = pConfig->crossover_probability;
+    }
+    pWorkspace->success_f = malloc(populationSize * sizeof(double));
+    pWorkspace->success_cr = malloc(populationSize * sizeof(double));
+    pWorkspace->success_weight = malloc(populationSize * sizeof(double));
+    pWorkspace->success_count = 0U;
+    uint32_t pool = de_strategy_pool(pConfig);
+    uint32_t poolSize = 0U;
+    for (uint32_t k = 0; k < DE_MUTATION_STRATEGY_COUNT; k++) {
+        poolSize += (pool & DE_STRATEGY_BIT(k)) != 0U;
+    }
+    for (uint32_t k = 0; k < DE_MUTATION_STRATEGY_COUNT; k++) {
+        pWorkspace->strategy_probability[k] = (pool & DE_STRATEGY_BIT(k)) != 0U ? 1.0 / (double)poolSize : 0.0;
+        pWorkspace->strategy_mean_cr[k] = pConfig->crossover_probability;
+        pWorkspace->strategy_trials[k] = 0U;
+        pWorkspace->strategy_successes[k] = 0U;
+    }
+    uint32_t windowLength = de_is_ensemble(pConfig) ? pConfig->learning_period * DE_MUTATION_STRATEGY_COUNT : 0U;
+    pWorkspace->window_successes = calloc(windowLength, sizeof(uint32_t));
+    pWorkspace->window_failures = calloc(windowLength, sizeof(uint32_t));
+    pWorkspace->window_success_cr = calloc(windowLength, sizeof(double));
+    pWorkspace->window_index = 0U;
+    pWorkspace->window_fill = 0U;
+    pWorkspace->archive_capacity = (uint32_t)round(pConfig->archive_rate * populationSize);
+    pWorkspace->archive.size = pWorkspace->archive_capacity;
+    de_population_allocate_members (&pWorkspace->archive);
+    for (uint32_t i = 0; i < pWorkspace->archive_capacity; i++) {
+        pVector_t pVec = &pWorkspace->archive.members[i];
+        pVec->num_dimensions = numDimensions;
+        de_vector_allocate_coordinates (pVec);
+    }
+    pWorkspace->archive.size = 0U;
+    pWorkspace->evaluation_count = 0U;
+    pWorkspace->stagnation_best_val = INFINITY;
+    pWorkspace->stagnation_best_violation = INFINITY;
+    pWorkspace->epsilon_level = 0.0;
+    pWorkspace->epsilon_initial = 0.0;
+    pWorkspace->last_improvement_iter = 0U;
+    timespec_get(&pWorkspace->start_time, TIME_UTC);
+    pWorkspace->history = NULL;
+    pWorkspace->history_length = 0U;
+    pWorkspace->history_capacity = 0U;
+    pWorkspace->batch_coordinates = NULL;
+    pWorkspace->worker_pool = NULL;
+    pWorkspace->evaluation_chunks = NULL;
+    de_workspace_start_workers(pWorkspace, pConfig->thread_count);
 
     for (uint32_t i = 0; i < pWorkspace->main_population.size; i++) {
         pVector_t pVec = &pWorkspace->main_population.members[i];
         pVec->num_dimensions = numDimensions;
         de_vector_allocate_coordinates (pVec);
+        pWorkspace->main_population.f[i] = pConfig->amplification_factor;
+        pWorkspace->main_population.cr[i] = pConfig->crossover_probability;
+        pWorkspace->main_population.strategy[i] = pConfig->mutation_strategy;
+        if (pConfig->adaptation == DE_ADAPTATION_EPSDE) {
+            de_redraw_epsde_parameters(pWorkspace, i);
+        }
     }
 
     for (uint32_t i = 0; i < pWorkspace->probe_population.size; i++) {
@@ -46,170 +399,1889 @@ void de_workspace_init (pDeWorkspace_t pWorkspace, uint32_t populationSize, uint
 }
 
 void de_workspace_deinit (pDeWorkspace_t pWorkspace) {
-    for (uint32_t i = 0; i < pWorkspace->main_population.size; i++) {
+    de_workspace_start_workers(pWorkspace, 0U);
+    for (uint32_t i = 0; i < pWorkspace->main_population.capacity; i++) {
         pVector_t pVec = &pWorkspace->main_population.members[i];
         de_vector_free_coordinates(pVec);
     }
 
-    for (uint32_t i = 0; i < pWorkspace->probe_population.size; i++) {
+    for (uint32_t i = 0; i < pWorkspace->probe_population.capacity; i++) {
         pVector_t pVec = &pWorkspace->probe_population.members[i];
         de_vector_free_coordinates(pVec);
     }
 
+    for (uint32_t i = 0; i < pWorkspace->archive.capacity; i++) {
+        de_vector_free_coordinates(&pWorkspace->archive.members[i]);
+    }
+
     de_population_free_members (&pWorkspace->main_population);
     de_population_free_members (&pWorkspace->probe_population);
+    de_population_free_members (&pWorkspace->archive);
+    free(pWorkspace->donors);
+    free(pWorkspace->ranking);
+    free(pWorkspace->memory_f);
+    free(pWorkspace->memory_cr);
+    free(pWorkspace->success_f);
+    free(pWorkspace->success_cr);
+    free(pWorkspace->success_weight);
+    free(pWorkspace->window_successes);
+    free(pWorkspace->window_failures);
+    free(pWorkspace->window_success_cr);
+    free(pWorkspace->batch_coordinates);
+    free(pWorkspace->history);
 }
 
-void de_workspace_set_best(pDeWorkspace_t pWorkspace, pVector_t pBest, pDeOptimizationTarget_t pTarget, void* pUserData) {
-    pWorkspace->p_main_best = pBest;
-    pWorkspace->p_main_best_val = pTarget->f(*pBest, pUserData);
+void de_workspace_set_best(pDeWorkspace_t pWorkspace, uint32_t bestIndex) {
+    pWorkspace->p_main_best = &pWorkspace->main_population.members[bestIndex];
+    pWorkspace->p_main_best_val = pWorkspace->main_population.fitness[bestIndex];
+    pWorkspace->p_main_best_violation = pWorkspace->main_population.violation[bestIndex];
 }
 
-void de_generate_main_population(pDeWorkspace_t pWorkspace, uint32_t numDimensions, double leftBound, double rightBound) {
-    for (uint32_t i = 0; i < pWorkspace->main_population.size; i++) {
-        pVector_t pVec = &pWorkspace->main_population.members[i];
-        for (uint32_t j = 0; j < numDimensions; j++) {
-            pVec->coordinates[j] = leftBound + (rightBound - leftBound) * fmod(genRand(&pWorkspace->mt_rand), 1.0);
-        }
+/**
+ * @returns 1 if a vector with fitness `fitnessA` and constraint violation `violationA` is better than one with `fitnessB` and `violationB`
+ * @note Without violations this is `fitnessA < fitnessB` for every constraint handling
+*/
+uint8_t de_is_better(pDeWorkspace_t pWorkspace, pDeConfig_t pConfig, double fitnessA, double violationA, double fitnessB, double violationB) {
+    if (pConfig->constraint_handling == DE_CONSTRAINTS_PENALTY) {
+        return fitnessA + pConfig->penalty_factor * violationA < fitnessB + pConfig->penalty_factor * violationB;
     }
+    double epsilon = pConfig->constraint_handling == DE_CONSTRAINTS_EPSILON ? pWorkspace->epsilon_level : 0.0;
+    if ((violationA <= epsilon && violationB <= epsilon) || violationA == violationB) {
+        return fitnessA < fitnessB;
+    }
+    return violationA < violationB;
 }
 
-pVector_t de_get_best(pDePopulation_t pPopulation, RdR_Function f, void* pUserData) {
-    pVector_t pBest = &pPopulation->members[0];
-    double bestVal = f(*pBest, pUserData);
-    for (uint32_t i = 0; i < pPopulation->size; i++) {
-        pVector_t pVec = &pPopulation->members[i];
-        double val = f(*pVec, pUserData);
-        if (val < bestVal) {
-            pBest = pVec;
-            bestVal = val;
+/**
+ * @returns 1 if member `a` of `pPopulationA` is better than member `b` of `pPopulationB`
+*/
+uint8_t de_is_better_member(pDeWorkspace_t pWorkspace, pDeConfig_t pConfig, pDePopulation_t pPopulationA, uint64_t a, pDePopulation_t pPopulationB, uint64_t b) {
+    return de_is_better(pWorkspace, pConfig, pPopulationA->fitness[a], pPopulationA->violation[a], pPopulationB->fitness[b], pPopulationB->violation[b]);
+}
+
+typedef struct {
+    uint32_t degree; // s
+    uint32_t coefficients; // a, the inner coefficients of the primitive polynomial
+    uint32_t initial_numbers[7]; // m_1 .. m_s
+} de_sobol_parameters_t;
+
+/** Joe & Kuo (new-joe-kuo-6.21201) parameters for dimensions 2 .. DE_SOBOL_MAX_DIMENSIONS, dimension 1 is the van der Corput sequence */
+static const de_sobol_parameters_t de_sobol_parameters[DE_SOBOL_MAX_DIMENSIONS - 1] = {
+    { 1, 0, { 1 } },
+    { 2, 1, { 1, 3 } },
+    { 3, 1, { 1, 3, 1 } },
+    { 3, 2, { 1, 1, 1 } },
+    { 4, 1, { 1, 1, 3, 3 } },
+    { 4, 4, { 1, 3, 5, 13 } },
+    { 5, 2, { 1, 1, 5, 5, 17 } },
+    { 5, 4, { 1, 1, 5, 5, 5 } },
+    { 5, 7, { 1, 1, 7, 11, 19 } },
+    { 5, 11, { 1, 1, 5, 1, 1 } },
+    { 5, 13, { 1, 1, 1, 3, 11 } },
+    { 5, 14, { 1, 3, 5, 5, 31 } },
+    { 6, 1, { 1, 3, 3, 9, 7, 49 } },
+    { 6, 13, { 1, 1, 1, 15, 21, 21 } },
+    { 6, 16, { 1, 3, 1, 13, 27, 49 } },
+    { 6, 19, { 1, 1, 1, 15, 7, 5 } },
+    { 6, 22, { 1, 3, 1, 15, 13, 25 } },
+    { 6, 25, { 1, 1, 5, 5, 19, 61 } },
+    { 7, 1, { 1, 3, 7, 11, 23, 15, 103 } },
+    { 7, 4, { 1, 3, 7, 13, 13, 15, 69 } },
+    { 7, 7, { 1, 1, 3, 13, 7, 35, 63 } },
+    { 7, 8, { 1, 3, 5, 9, 1, 25, 53 } },
+    { 7, 14, { 1, 3, 1, 13, 9, 35, 107 } },
+    { 7, 19, { 1, 3, 1, 5, 27, 61, 31 } },
+    { 7, 21, { 1, 1, 5, 11, 19, 41, 61 } },
+    { 7, 28, { 1, 3, 5, 3, 3, 13, 69 } },
+    { 7, 31, { 1, 1, 7, 13, 1, 19, 1 } },
+    { 7, 32, { 1, 3, 7, 5, 13, 19, 59 } },
+    { 7, 37, { 1, 1, 3, 9, 25, 29, 41 } }
+};
+
+/**
+ * @returns Coordinate `dimension` of Sobol point `index` in [0; 1)
+*/
+double de_sobol_coordinate(uint32_t index, uint32_t dimension) {
+    uint32_t directions[32];
+    for (uint32_t k = 0; k < 32U; k++) {
+        if (dimension == 0U) {
+            directions[k] = 1U << (31U - k);
+            continue;
+        }
+        const de_sobol_parameters_t* pParameters = &de_sobol_parameters[dimension - 1U];
+        uint32_t s = pParameters->degree;
+        if (k < s) {
+            directions[k] = pParameters->initial_numbers[k] << (31U - k);
+            continue;
+        }
+        directions[k] = directions[k - s] ^ (directions[k - s] >> s);
+        for (uint32_t j = 1; j < s; j++) {
+            if ((pParameters->coefficients >> (s - 1U - j)) & 1U) {
+                directions[k] ^= directions[k - j];
+            }
+        }
+    }
+    uint32_t grayCode = index ^ (index >> 1);
+    uint32_t x = 0U;
+    for (uint32_t k = 0; grayCode != 0U; k++, grayCode >>= 1) {
+        if (grayCode & 1U) {
+            x ^= directions[k];
         }
     }
-    return pBest;
+    return (double)x / 4294967296.0;
 }
 
 /**
- * Reproduce the population, so that it can be mutated with DE/rand_best/1/bin
+ * @returns Radical inverse of `index` in the given base, in [0; 1)
 */
-void de_reproduce(pDeWorkspace_t pWorkspace, pDeOptimizationTarget_t pTarget, pDeConfig_t pConfig, void* pUserData) {
-    pVector_t pBest = de_get_best(&pWorkspace->main_population, pTarget->f, pUserData);
-    de_workspace_set_best(pWorkspace, pBest, pTarget, pUserData); // Let the workspace know who is the best
-    double lambda = pConfig->lambda;
+double de_halton_coordinate(uint32_t index, uint32_t base) {
+    double x = 0.0;
+    double digitWeight = 1.0 / base;
+    while (index > 0U) {
+        x += (index % base) * digitWeight;
+        index /= base;
+        digitWeight /= base;
+    }
+    return x;
+}
 
-    for (uint32_t i = 0; i < pWorkspace->main_population.size; i++) {
-        pVector_t pMainVec = &pWorkspace->main_population.members[i];
-        pVector_t pProbeVec = &pWorkspace->probe_population.members[i];
-        //memcpy (pProbeVec->coordinates, pMainVec->coordinates, pMainVec->num_dimensions * sizeof(double));
-        for (uint32_t j = 0; j < pProbeVec->num_dimensions; j++) {
-            pProbeVec->coordinates[j] = lambda * pBest->coordinates[j] + (1 - lambda) * pMainVec->coordinates[j];
+uint32_t de_next_prime(uint32_t n) {
+    for (n++;; n++) {
+        uint32_t divisor = 2U;
+        while (divisor * divisor <= n && n % divisor != 0U) {
+            divisor++;
+        }
+        if (divisor * divisor > n) {
+            return n;
         }
     }
 }
 
-void de_mutate(pDeWorkspace_t pWorkspace, pDeOptimizationTarget_t pTarget, pDeConfig_t pConfig) {
-    double F = pConfig->amplification_factor;
-    double leftBound = pTarget->left_bound;
-    double rightBound = pTarget->right_bound;
+/**
+ * @returns The type of coordinate `j`, DE_VARIABLE_CONTINUOUS without `variable_types`
+*/
+de_variable_type_t de_variable_type(pDeOptimizationTarget_t pTarget, uint32_t j) {
+    return pTarget->variable_types != NULL ? pTarget->variable_types[j] : DE_VARIABLE_CONTINUOUS;
+}
 
-    for (uint32_t i = 0; i < pWorkspace->probe_population.size; i++) {
-        pVector_t pProbeVec = &pWorkspace->probe_population.members[i];
-        uint32_t r2;
-        uint32_t r3;
-        do {
-            r2 = genRandLong(&pWorkspace->mt_rand) % pWorkspace->main_population.size;
-        } while (r2 == i);
-        do {
-            r3 = genRandLong(&pWorkspace->mt_rand) % pWorkspace->main_population.size;
-        } while (r3 == i || r3 == r2);
-        pVector_t pX2 = &pWorkspace->main_population.members[r2];
-        pVector_t pX3 = &pWorkspace->main_population.members[r3];
-        for (uint32_t j = 0; j < pProbeVec->num_dimensions; j++) {
-            pProbeVec->coordinates[j] += F * (pX2->coordinates[j] - pX3->coordinates[j]);
-            if (pProbeVec->coordinates[j] < leftBound) {
-                pProbeVec->coordinates[j] = leftBound;
-            } else if (pProbeVec->coordinates[j] > rightBound) {
-                pProbeVec->coordinates[j] = rightBound;
+/**
+ * @returns `u` from [0; 1) mapped onto coordinate `j`: its continuous range, or one of the integers within its bounds with equal probability
+*/
+double de_scale_coordinate(pDeOptimizationTarget_t pTarget, uint32_t j, double u) {
+    double leftBound = pTarget->left_bounds[j];
+    double rightBound = pTarget->right_bounds[j];
+    if (de_variable_type(pTarget, j) == DE_VARIABLE_CONTINUOUS) {
+        return leftBound + (rightBound - leftBound) * u;
+    }
+    double lowest = ceil(leftBound);
+    return fmin(lowest + floor(u * (floor(rightBound) - lowest + 1.0)), floor(rightBound));
+}
+
+/**
+ * @returns `x` unchanged for a continuous coordinate `j`, otherwise rounded to the nearest integer within its bounds
+*/
+double de_repair_coordinate(pDeOptimizationTarget_t pTarget, uint32_t j, double x) {
+    if (de_variable_type(pTarget, j) == DE_VARIABLE_CONTINUOUS) {
+        return x;
+    }
+    return fmin(fmax(round(x), ceil(pTarget->left_bounds[j])), floor(pTarget->right_bounds[j]));
+}
+
+/**
+ * Fill the main population with the warm-start rows of the target (if any), followed by members placed per `pConfig->initialization`
+ * @note DE_INIT_OPPOSITION draws uniformly here, the opposites are added by `de_apply_opposition` once the population is evaluated
+*/
+void de_generate_main_population(pDeWorkspace_t pWorkspace, pDeOptimizationTarget_t pTarget, pDeConfig_t pConfig) {
+    uint32_t warmCount = pTarget->initial_population_size;
+    uint32_t generatedCount = (uint32_t)pWorkspace->main_population.size - warmCount;
+    for (uint32_t i = 0; i < warmCount; i++) {
+        pVector_t pVec = &pWorkspace->main_population.members[i];
+        for (uint32_t j = 0; j < pTarget->num_dimensions; j++) {
+            pVec->coordinates[j] = pTarget->initial_population[i * pTarget->num_dimensions + j];
+        }
+    }
+    if (pConfig->initialization == DE_INIT_UNIFORM || pConfig->initialization == DE_INIT_OPPOSITION) {
+        // Member by member, so seeded runs draw the same numbers as before the other initializations existed
+        for (uint32_t i = warmCount; i < pWorkspace->main_population.size; i++) {
+            pVector_t pVec = &pWorkspace->main_population.members[i];
+            for (uint32_t j = 0; j < pTarget->num_dimensions; j++) {
+                pVec->coordinates[j] = de_scale_coordinate(pTarget, j, fmod(genRand(&pWorkspace->mt_rand), 1.0));
             }
-            if (pProbeVec->coordinates[j] < leftBound || pProbeVec->coordinates[j] > rightBound) {
-                fprintf(stderr, "[DE] Error: Coordinate out of bounds in mutation\n");
+        }
+        return;
+    }
+    uint32_t* strata = pConfig->initialization == DE_INIT_LATIN_HYPERCUBE ? malloc(generatedCount * sizeof(uint32_t)) : NULL;
+    uint32_t haltonBase = 1U;
+    for (uint32_t j = 0; j < pTarget->num_dimensions; j++) {
+        double shift = 0.0; // Cranley-Patterson rotation of the quasi-random sequences
+        if (pConfig->initialization == DE_INIT_LATIN_HYPERCUBE) {
+            // Fisher-Yates shuffle of the strata of this dimension
+            for (uint32_t i = 0; i < generatedCount; i++) {
+                strata[i] = i;
             }
+            for (uint32_t i = generatedCount; i > 1U; i--) {
+                uint32_t k = genRandLong(&pWorkspace->mt_rand) % i;
+                uint32_t tmp = strata[i - 1U]; strata[i - 1U] = strata[k]; strata[k] = tmp;
+            }
+        } else if (pConfig->initialization == DE_INIT_HALTON || pConfig->initialization == DE_INIT_SOBOL) {
+            shift = fmod(genRand(&pWorkspace->mt_rand), 1.0);
+            haltonBase = de_next_prime(haltonBase);
+        }
+        for (uint32_t i = 0; i < generatedCount; i++) {
+            double u;
+            switch (pConfig->initialization) {
+                case DE_INIT_LATIN_HYPERCUBE:
+                    u = (strata[i] + fmod(genRand(&pWorkspace->mt_rand), 1.0)) / generatedCount;
+                    break;
+                case DE_INIT_HALTON:
+                    u = fmod(de_halton_coordinate(i + 1U, haltonBase) + shift, 1.0);
+                    break;
+                default:
+                    u = fmod(de_sobol_coordinate(i + 1U, j) + shift, 1.0);
+                    break;
+            }
+            pWorkspace->main_population.members[warmCount + i].coordinates[j] = de_scale_coordinate(pTarget, j, u);
         }
     }
+    free(strata);
 }
 
-void de_crossover(pDeWorkspace_t pWorkspace, pDeConfig_t pConfig) {
-    double CR = pConfig->crossover_probability;
+/**
+ * Evaluate `count` members of the population starting at `first`, in one `f_batch` call if the target has one,
+ * split into contiguous chunks over the worker pool (the calling thread takes the first chunk),
+ * batches smaller than the thread count stay on the calling thread
+*/
+void de_evaluate_members(pDeWorkspace_t pWorkspace, pDeOptimizationTarget_t pTarget, pDeConfig_t pConfig, pDePopulation_t pPopulation, uint32_t first, uint32_t count, void* pUserData) {
+    pWorkspace->evaluation_count += count;
+    uint32_t numDimensions = pTarget->num_dimensions;
+    if (pTarget->f_batch != NULL) {
+        if (pWorkspace->batch_coordinates == NULL) {
+            pWorkspace->batch_coordinates = malloc(pWorkspace->main_population.capacity * numDimensions * sizeof(double));
+        }
+        for (uint32_t i = 0; i < count; i++) {
+            for (uint32_t j = 0; j < numDimensions; j++) {
+                pWorkspace->batch_coordinates[i * numDimensions + j] = pPopulation->members[first + i].coordinates[j];
+            }
+        }
+    }
 
-    for (uint32_t i = 0; i < pWorkspace->probe_population.size; i++) {
-        pVector_t pProbeVec = &pWorkspace->probe_population.members[i];
-        pVector_t pMainVec = &pWorkspace->main_population.members[i];
+    de_worker_pool_t* pPool = pWorkspace->worker_pool;
+    uint32_t threadCount = pPool != NULL ? pPool->thread_count + 1U : 1U;
+    if (count < threadCount || threadCount == 1U) {
+        void* pChunkUserData = pTarget->thread_user_data != NULL ? pTarget->thread_user_data[0] : pUserData; // the calling thread is thread 0
+        de_evaluation_chunk_t chunk = { pTarget, pPopulation, first, count, pWorkspace->batch_coordinates, pConfig->equality_tolerance, pChunkUserData };
+        de_evaluate_chunk(&chunk);
+        return;
+    }
+    uint32_t chunkFirst = first;
+    for (uint32_t t = 0; t < threadCount; t++) {
+        uint32_t chunkCount = count / threadCount + (t < count % threadCount ? 1U : 0U);
+        pWorkspace->evaluation_chunks[t] = (de_evaluation_chunk_t){
+            .pTarget = pTarget,
+            .pPopulation = pPopulation,
+            .first = chunkFirst,
+            .count = chunkCount,
+            .pBatchCoordinates = pWorkspace->batch_coordinates != NULL ? &pWorkspace->batch_coordinates[(chunkFirst - first) * numDimensions] : NULL,
+            .equalityTolerance = pConfig->equality_tolerance,
+            .pUserData = pTarget->thread_user_data != NULL ? pTarget->thread_user_data[t] : pUserData
+        };
+        chunkFirst += chunkCount;
+    }
+    de_worker_pool_run(pPool, de_evaluate_chunk, pWorkspace->evaluation_chunks, sizeof(de_evaluation_chunk_t), threadCount);
+}
 
-        uint32_t d = genRandLong(&pWorkspace->mt_rand) % pProbeVec->num_dimensions;
-        for (uint32_t j = 0; j < pProbeVec->num_dimensions; j++) {
-            double r = fmod(genRand(&pWorkspace->mt_rand), 1.0);
-            if (r < CR || j == d) {
-                // do nothing
-            } else {
-                pProbeVec->coordinates[j] = pMainVec->coordinates[j];
+void de_evaluate_population(pDeWorkspace_t pWorkspace, pDeOptimizationTarget_t pTarget, pDeConfig_t pConfig, pDePopulation_t pPopulation, void* pUserData) {
+    de_evaluate_members(pWorkspace, pTarget, pConfig, pPopulation, 0U, (uint32_t)pPopulation->size, pUserData);
+}
+
+/**
+ * Opposition-based initialization: evaluate the opposite l + r - x of every generated (non warm-start) member
+ * and keep the fittest half of members and opposites, the probe population serves as scratch space
+*/
+void de_apply_opposition(pDeWorkspace_t pWorkspace, pDeOptimizationTarget_t pTarget, pDeConfig_t pConfig, void* pUserData) {
+    uint32_t warmCount = pTarget->initial_population_size;
+    uint32_t generatedCount = (uint32_t)pWorkspace->main_population.size - warmCount;
+    pDePopulation_t pMain = &pWorkspace->main_population;
+    pDePopulation_t pOpposites = &pWorkspace->probe_population;
+    for (uint32_t i = warmCount; i < pMain->size; i++) {
+        for (uint32_t j = 0; j < pTarget->num_dimensions; j++) {
+            pOpposites->members[i].coordinates[j] = de_repair_coordinate(pTarget, j, pTarget->left_bounds[j] + pTarget->right_bounds[j] - pMain->members[i].coordinates[j]);
+        }
+    }
+    de_evaluate_members(pWorkspace, pTarget, pConfig, pOpposites, warmCount, generatedCount, pUserData);
+
+    // Candidates 0 .. generatedCount - 1 are the members, generatedCount .. 2 * generatedCount - 1 their opposites
+    uint32_t candidateCount = 2U * generatedCount;
+    uint32_t* candidates = malloc(candidateCount * sizeof(uint32_t));
+    for (uint32_t c = 0; c < candidateCount; c++) {
+        uint32_t k = c;
+        pDePopulation_t pCandidate = c < generatedCount ? pMain : pOpposites;
+        uint32_t candidateIndex = warmCount + (c < generatedCount ? c : c - generatedCount);
+        for (; k > 0U; k--) {
+            uint32_t other = candidates[k - 1U];
+            pDePopulation_t pOther = other < generatedCount ? pMain : pOpposites;
+            uint32_t otherIndex = warmCount + (other < generatedCount ? other : other - generatedCount);
+            if (!de_is_better_member(pWorkspace, pConfig, pCandidate, candidateIndex, pOther, otherIndex)) {
+                break;
             }
+            candidates[k] = candidates[k - 1U];
+        }
+        candidates[k] = c;
+    }
+
+    // Every member that did not make the cut is replaced by an opposite that did, their counts are equal
+    uint8_t* isKept = calloc(generatedCount, sizeof(uint8_t));
+    for (uint32_t c = 0; c < generatedCount; c++) {
+        if (candidates[c] < generatedCount) {
+            isKept[candidates[c]] = 1U;
+        }
+    }
+    uint32_t slot = 0U;
+    for (uint32_t c = 0; c < generatedCount; c++) {
+        if (candidates[c] < generatedCount) {
+            continue;
+        }
+        while (isKept[slot]) {
+            slot++;
+        }
+        uint32_t source = warmCount + candidates[c] - generatedCount;
+        for (uint32_t j = 0; j < pTarget->num_dimensions; j++) {
+            pMain->members[warmCount + slot].coordinates[j] = pOpposites->members[source].coordinates[j];
+        }
+        pMain->fitness[warmCount + slot] = pOpposites->fitness[source];
+        pMain->violation[warmCount + slot] = pOpposites->violation[source];
+        slot++;
+    }
+    free(isKept);
+    free(candidates);
+}
+
+/**
+ * @returns Index of the best member by cached fitness and violation, see `de_is_better`
+*/
+uint32_t de_get_best(pDeWorkspace_t pWorkspace, pDeConfig_t pConfig, pDePopulation_t pPopulation) {
+    uint32_t bestIndex = 0U;
+    for (uint32_t i = 1; i < pPopulation->size; i++) {
+        if (de_is_better_member(pWorkspace, pConfig, pPopulation, i, pPopulation, bestIndex)) {
+            bestIndex = i;
+        }
+    }
+    return bestIndex;
+}
+
+uint32_t de_mutation_donor_count(de_mutation_strategy_t strategy) {
+    switch (strategy) {
+        case DE_MUTATION_RAND_1:
+            return 3U;
+        case DE_MUTATION_RAND_2:
+            return 5U;
+        case DE_MUTATION_BEST_2:
+            return 4U;
+        case DE_MUTATION_RAND_BEST_1:
+        case DE_MUTATION_BEST_1:
+        case DE_MUTATION_CURRENT_TO_BEST_1:
+        case DE_MUTATION_CURRENT_TO_PBEST_1:
+        default:
+            return 2U;
+    }
+}
+
+/**
+ * @returns The largest `de_mutation_donor_count` of the strategies in `pool`
+*/
+uint32_t de_pool_donor_count(uint32_t pool) {
+    uint32_t donorCount = 0U;
+    for (uint32_t k = 0; k < DE_MUTATION_STRATEGY_COUNT; k++) {
+        if ((pool & DE_STRATEGY_BIT(k)) != 0U && de_mutation_donor_count((de_mutation_strategy_t)k) > donorCount) {
+            donorCount = de_mutation_donor_count((de_mutation_strategy_t)k);
         }
     }
+    return donorCount;
 }
 
-void de_select(pDeWorkspace_t pWorkspace, pDeOptimizationTarget_t pTarget, void* pUserData) {
+double de_random_normal(pDeWorkspace_t pWorkspace, double mean, double deviation) {
+    double u1 = 1.0 - fmod(genRand(&pWorkspace->mt_rand), 1.0); // (0; 1] for the logarithm
+    double u2 = fmod(genRand(&pWorkspace->mt_rand), 1.0);
+    return mean + deviation * sqrt(-2.0 * log(u1)) * cos(2.0 * DE_PI * u2);
+}
+
+double de_random_cauchy(pDeWorkspace_t pWorkspace, double location, double scale) {
+    return location + scale * tan(DE_PI * (fmod(genRand(&pWorkspace->mt_rand), 1.0) - 0.5));
+}
+
+/**
+ * Draw the strategy, F and CR of every probe for this generation, the configured constants unless adaptation is enabled
+*/
+void de_sample_parameters(pDeWorkspace_t pWorkspace, pDeConfig_t pConfig) {
+    pWorkspace->success_count = 0U;
     for (uint32_t i = 0; i < pWorkspace->main_population.size; i++) {
-        pVector_t pMainVec = &pWorkspace->main_population.members[i];
-        pVector_t pProbeVec = &pWorkspace->probe_population.members[i];
-        double mainVal = pTarget->f(*pMainVec, pUserData);
-        double probeVal = pTarget->f(*pProbeVec, pUserData);
-        if (probeVal < mainVal) {
-            for (uint32_t j = 0; j < pMainVec->num_dimensions; j++) {
-                pMainVec->coordinates[j] = pProbeVec->coordinates[j];
+        pWorkspace->probe_population.strategy[i] = pConfig->mutation_strategy;
+        if (pConfig->adaptation == DE_ADAPTATION_EPSDE) {
+            pWorkspace->probe_population.strategy[i] = pWorkspace->main_population.strategy[i];
+            pWorkspace->probe_population.f[i] = pWorkspace->main_population.f[i];
+            pWorkspace->probe_population.cr[i] = pWorkspace->main_population.cr[i];
+            continue;
+        }
+        if (pConfig->adaptation == DE_ADAPTATION_SADE) {
+            de_mutation_strategy_t strategy = de_draw_strategy(pWorkspace);
+            pWorkspace->probe_population.strategy[i] = strategy;
+            double CR = de_random_normal(pWorkspace, pWorkspace->strategy_mean_cr[strategy], 0.1);
+            pWorkspace->probe_population.cr[i] = CR < 0.0 ? 0.0 : (CR > 1.0 ? 1.0 : CR);
+            double F;
+            do {
+                F = de_random_normal(pWorkspace, DE_SADE_F_MEAN, DE_SADE_F_DEVIATION);
+            } while (!(F > 0.0));
+            pWorkspace->probe_population.f[i] = F > 2.0 ? 2.0 : F;
+            continue;
+        }
+        if (pConfig->adaptation == DE_ADAPTATION_NONE) {
+            pWorkspace->probe_population.f[i] = pConfig->amplification_factor;
+            pWorkspace->probe_population.cr[i] = pConfig->crossover_probability;
+            continue;
+        }
+        if (pConfig->adaptation == DE_ADAPTATION_JDE) {
+            // Inherit the parent's parameters, each regenerated with probability tau1 / tau2
+            pWorkspace->probe_population.f[i] = fmod(genRand(&pWorkspace->mt_rand), 1.0) < pConfig->jde_tau1
+                ? DE_JDE_F_LOWER + DE_JDE_F_RANGE * fmod(genRand(&pWorkspace->mt_rand), 1.0) : pWorkspace->main_population.f[i];
+            pWorkspace->probe_population.cr[i] = fmod(genRand(&pWorkspace->mt_rand), 1.0) < pConfig->jde_tau2
+                ? fmod(genRand(&pWorkspace->mt_rand), 1.0) : pWorkspace->main_population.cr[i];
+            continue;
+        }
+        double meanF = pWorkspace->mean_f;
+        double meanCR = pWorkspace->mean_cr;
+        if (pConfig->adaptation == DE_ADAPTATION_SHADE) {
+            uint32_t slot = genRandLong(&pWorkspace->mt_rand) % pWorkspace->memory_size;
+            meanF = pWorkspace->memory_f[slot];
+            meanCR = pWorkspace->memory_cr[slot];
+        }
+        double CR = de_random_normal(pWorkspace, meanCR, 0.1);
+        pWorkspace->probe_population.cr[i] = CR < 0.0 ? 0.0 : (CR > 1.0 ? 1.0 : CR);
+        double F;
+        do {
+            F = de_random_cauchy(pWorkspace, meanF, 0.1);
+        } while (!(F > 0.0)); // also redraws NaN
+        pWorkspace->probe_population.f[i] = F > 1.0 ? 1.0 : F;
+    }
+}
+
+/**
+ * Recompute the strategy probabilities and SaDE's CR means from the learning period, then start the next row of the window
+*/
+void de_adapt_ensemble(pDeWorkspace_t pWorkspace, pDeConfig_t pConfig) {
+    uint32_t learningPeriod = pConfig->learning_period;
+    if (pWorkspace->window_fill < learningPeriod) {
+        pWorkspace->window_fill++;
+    }
+    if (pWorkspace->window_fill == learningPeriod) {
+        uint32_t pool = de_strategy_pool(pConfig);
+        double rateSum = 0.0;
+        for (uint32_t k = 0; k < DE_MUTATION_STRATEGY_COUNT; k++) {
+            pWorkspace->strategy_probability[k] = 0.0;
+            if ((pool & DE_STRATEGY_BIT(k)) == 0U) {
+                continue;
+            }
+            uint64_t successes = 0U; uint64_t failures = 0U; double crSum = 0.0;
+            for (uint32_t row = 0; row < learningPeriod; row++) {
+                successes += pWorkspace->window_successes[row * DE_MUTATION_STRATEGY_COUNT + k];
+                failures += pWorkspace->window_failures[row * DE_MUTATION_STRATEGY_COUNT + k];
+                crSum += pWorkspace->window_success_cr[row * DE_MUTATION_STRATEGY_COUNT + k];
+            }
+            pWorkspace->strategy_probability[k] = DE_ENSEMBLE_SUCCESS_RATE_FLOOR + (successes > 0U ? (double)successes / (double)(successes + failures) : 0.0);
+            rateSum += pWorkspace->strategy_probability[k];
+            if (successes > 0U) {
+                pWorkspace->strategy_mean_cr[k] = crSum / (double)successes;
             }
         }
+        for (uint32_t k = 0; k < DE_MUTATION_STRATEGY_COUNT; k++) {
+            pWorkspace->strategy_probability[k] /= rateSum;
+        }
+    }
+    pWorkspace->window_index = (pWorkspace->window_index + 1U) % learningPeriod;
+    for (uint32_t k = 0; k < DE_MUTATION_STRATEGY_COUNT; k++) {
+        uint32_t cell = pWorkspace->window_index * DE_MUTATION_STRATEGY_COUNT + k;
+        pWorkspace->window_successes[cell] = 0U;
+        pWorkspace->window_failures[cell] = 0U;
+        pWorkspace->window_success_cr[cell] = 0.0;
     }
 }
 
 /**
- * @returns 1 if stop condition is satisfied, otherwise 0
+ * Move the F/CR means towards the parameters of this generation's successful probes
 */
-uint8_t de_check_stop_condition(uint64_t iter_count, pDeConfig_t pConfig, pDeWorkspace_t pWorkspace) {
-    switch (pConfig->stop_condition.type) {
-        case STOP_AFTER_ITERS:
-            return iter_count > pConfig->stop_condition.limitation.iters;
-        case STOP_WHEN_SATISFIED:
-            return pWorkspace->p_main_best_val < pConfig->stop_condition.limitation.accuracy;
-        default:
-            fprintf(stderr, "[DE] Error: Unknown stop condition provided\n");
-            return 1U;
+void de_adapt_parameters(pDeWorkspace_t pWorkspace, pDeConfig_t pConfig) {
+    if (de_is_ensemble(pConfig)) {
+        de_adapt_ensemble(pWorkspace, pConfig);
+        return;
+    }
+    if (pConfig->adaptation == DE_ADAPTATION_NONE || pConfig->adaptation == DE_ADAPTATION_JDE || pWorkspace->success_count == 0U) {
+        return;
+    }
+    double weightSum = 0.0;
+    for (uint32_t k = 0; k < pWorkspace->success_count; k++) {
+        // JADE weighs every success equally, SHADE by the fitness improvement
+        weightSum += pConfig->adaptation == DE_ADAPTATION_SHADE ? pWorkspace->success_weight[k] : 1.0;
+    }
+    double crMean = 0.0; double fSquareSum = 0.0; double fSum = 0.0;
+    for (uint32_t k = 0; k < pWorkspace->success_count; k++) {
+        double w = (pConfig->adaptation == DE_ADAPTATION_SHADE ? pWorkspace->success_weight[k] : 1.0) / weightSum;
+        crMean += w * pWorkspace->success_cr[k];
+        fSquareSum += w * pWorkspace->success_f[k] * pWorkspace->success_f[k];
+        fSum += w * pWorkspace->success_f[k];
+    }
+    double fLehmerMean = fSquareSum / fSum;
+    if (pConfig->adaptation == DE_ADAPTATION_SHADE) {
+        pWorkspace->memory_cr[pWorkspace->memory_index] = crMean;
+        pWorkspace->memory_f[pWorkspace->memory_index] = fLehmerMean;
+        pWorkspace->memory_index = (pWorkspace->memory_index + 1U) % pWorkspace->memory_size;
+    } else {
+        double c = pConfig->adaptation_rate;
+        pWorkspace->mean_cr = (1.0 - c) * pWorkspace->mean_cr + c * crMean;
+        pWorkspace->mean_f = (1.0 - c) * pWorkspace->mean_f + c * fLehmerMean;
     }
 }
 
-vector_t de_minimum(de_optimization_target_t* pOptimizationTarget, de_config_t* pConfig, void* pUserData) {
-    
-    de_workspace_t workspace;
-    de_workspace_init (&workspace, pConfig->population_size, pOptimizationTarget->num_dimensions);
-    de_generate_main_population (&workspace, pOptimizationTarget->num_dimensions, pOptimizationTarget->left_bound, pOptimizationTarget->right_bound);
-    
-    uint64_t iter_count = 0U;
-    do {
-        de_reproduce (&workspace, pOptimizationTarget, pConfig, pUserData);
-        de_mutate (&workspace, pOptimizationTarget, pConfig);
-        de_crossover (&workspace, pConfig);
-        de_select (&workspace, pOptimizationTarget, pUserData);
-        iter_count++;
-    } while (!de_check_stop_condition(iter_count, pConfig, &workspace));
-    
-    pVector_t pBest = de_get_best (&workspace.main_population, pOptimizationTarget->f, pUserData);
+/**
+ * Put a replaced parent into the external archive, overwriting a random entry once it is full
+*/
+void de_archive_parent(pDeWorkspace_t pWorkspace, pVector_t pParent) {
+    if (pWorkspace->archive_capacity == 0U) {
+        return;
+    }
+    uint32_t slot = (uint32_t)pWorkspace->archive.size;
+    if (slot == pWorkspace->archive_capacity) {
+        slot = genRandLong(&pWorkspace->mt_rand) % pWorkspace->archive_capacity;
+    } else {
+        pWorkspace->archive.size++;
+    }
+    for (uint32_t j = 0; j < pParent->num_dimensions; j++) {
+        pWorkspace->archive.members[slot].coordinates[j] = pParent->coordinates[j];
+    }
+}
 
-    vector_t retVec = { .num_dimensions = pBest->num_dimensions };
-    de_vector_allocate_coordinates (&retVec);
-    for (uint32_t i = 0; i < retVec.num_dimensions; i++) {
-        retVec.coordinates[i] = pBest->coordinates[i];
+/**
+ * @returns Member `index` of the union of the main population and the archive (archive members come after the main population)
+*/
+pVector_t de_population_or_archive_member(pDeWorkspace_t pWorkspace, uint32_t index) {
+    if (index < pWorkspace->main_population.size) {
+        return &pWorkspace->main_population.members[index];
     }
+    return &pWorkspace->archive.members[index - pWorkspace->main_population.size];
+}
 
-    de_workspace_deinit (&workspace);
+/**
+ * Sort the main population indices from best to worst into `pWorkspace->ranking`
+*/
+void de_rank_main_population(pDeWorkspace_t pWorkspace, pDeConfig_t pConfig) {
+    pDePopulation_t pMain = &pWorkspace->main_population;
+    for (uint32_t i = 0; i < pMain->size; i++) {
+        uint32_t j = i;
+        for (; j > 0 && de_is_better_member(pWorkspace, pConfig, pMain, i, pMain, pWorkspace->ranking[j - 1]); j--) { // insertion sort, NP is small
+            pWorkspace->ranking[j] = pWorkspace->ranking[j - 1];
+        }
+        pWorkspace->ranking[j] = i;
+    }
+}
+
+/**
+ * Draw `count` distinct main population indices, all different from `current`, into `pDonors`
+*/
+void de_draw_donors(pDeWorkspace_t pWorkspace, uint32_t current, uint32_t* pDonors, uint32_t count) {
+    for (uint32_t k = 0; k < count; k++) {
+        uint8_t isTaken;
+        do {
+            pDonors[k] = genRandLong(&pWorkspace->mt_rand) % pWorkspace->main_population.size;
+            isTaken = pDonors[k] == current;
+            for (uint32_t l = 0; l < k && !isTaken; l++) {
+                isTaken = pDonors[k] == pDonors[l];
+            }
+        } while (isTaken);
+    }
+}
+
+/**
+ * Reproduce the population: draw the donors of every probe and fill the probe with the base vector of its mutation strategy
+*/
+void de_reproduce(pDeWorkspace_t pWorkspace, pDeOptimizationTarget_t pTarget, pDeConfig_t pConfig) {
+    pVector_t pBest = pWorkspace->p_main_best;
+    if ((de_strategy_pool(pConfig) & DE_STRATEGY_BIT(DE_MUTATION_CURRENT_TO_PBEST_1)) != 0U) {
+        de_rank_main_population(pWorkspace, pConfig);
+    }
+    double lambda = pConfig->lambda;
+    uint32_t pBestCount = (uint32_t)ceil(pConfig->p_best_fraction * (double)pWorkspace->main_population.size);
+    if (pBestCount < 1U) {
+        pBestCount = 1U;
+    } else if (pBestCount > pWorkspace->main_population.size) {
+        pBestCount = (uint32_t)pWorkspace->main_population.size;
+    }
 
-    return retVec;
-    
-}
\ No newline at end of file
+    for (uint32_t i = 0; i < pWorkspace->main_population.size; i++) {
+        pVector_t pMainVec = &pWorkspace->main_population.members[i];
+        pVector_t pProbeVec = &pWorkspace->probe_population.members[i];
+        uint32_t* pDonors = &pWorkspace->donors[i * DE_MAX_DONORS];
+        de_mutation_strategy_t strategy = pWorkspace->probe_population.strategy[i];
+        de_draw_donors(pWorkspace, i, pDonors, de_mutation_donor_count(strategy));
+        if (strategy == DE_MUTATION_CURRENT_TO_PBEST_1 && pWorkspace->archive.size > 0U) {
+            // JADE: x_r2 comes from the union of the population and the archive
+            uint32_t unionSize = (uint32_t)(pWorkspace->main_population.size + pWorkspace->archive.size);
+            do {
+                pDonors[1] = genRandLong(&pWorkspace->mt_rand) % unionSize;
+            } while (pDonors[1] == i || pDonors[1] == pDonors[0]);
+        }
+        double F = pWorkspace->probe_population.f[i];
+        pVector_t pDonor = &pWorkspace->main_population.members[pDonors[0]];
+        pVector_t pPBest = pBest;
+        if (strategy == DE_MUTATION_CURRENT_TO_PBEST_1) {
+            pPBest = &pWorkspace->main_population.members[pWorkspace->ranking[genRandLong(&pWorkspace->mt_rand) % pBestCount]];
+        }
+
+        for (uint32_t j = 0; j < pProbeVec->num_dimensions; j++) {
+            switch (strategy) {
+                case DE_MUTATION_RAND_1:
+                case DE_MUTATION_RAND_2:
+                    pProbeVec->coordinates[j] = pDonor->coordinates[j];
+                    break;
+                case DE_MUTATION_BEST_1:
+                case DE_MUTATION_BEST_2:
+                    pProbeVec->coordinates[j] = pBest->coordinates[j];
+                    break;
+                case DE_MUTATION_CURRENT_TO_BEST_1:
+                case DE_MUTATION_CURRENT_TO_PBEST_1:
+                    pProbeVec->coordinates[j] = pMainVec->coordinates[j] + F * (pPBest->coordinates[j] - pMainVec->coordinates[j]);
+                    break;
+                case DE_MUTATION_RAND_BEST_1:
+                default:
+                    pProbeVec->coordinates[j] = lambda * pBest->coordinates[j] + (1 - lambda) * pMainVec->coordinates[j];
+                    break;
+            }
+            if (de_variable_type(pTarget, j) == DE_VARIABLE_CATEGORICAL
+                && (strategy == DE_MUTATION_RAND_BEST_1 || strategy == DE_MUTATION_CURRENT_TO_BEST_1 || strategy == DE_MUTATION_CURRENT_TO_PBEST_1)) {
+                // Categories cannot be blended, take one of the two vectors with the weight the blend would give it
+                double weight = strategy == DE_MUTATION_RAND_BEST_1 ? lambda : F;
+                pProbeVec->coordinates[j] = fmod(genRand(&pWorkspace->mt_rand), 1.0) < weight ? pPBest->coordinates[j] : pMainVec->coordinates[j];
+            }
+        }
+    }
+}
+
+/**
+ * @returns `x` brought back into [leftBound; rightBound] according to `handling`
+*/
+double de_handle_boundary(pDeWorkspace_t pWorkspace, de_boundary_handling_t handling, double x, double parentX, double leftBound, double rightBound) {
+    if (x >= leftBound && x <= rightBound) {
+        return x;
+    }
+    switch (handling) {
+        case DE_BOUNDARY_REFLECT: {
+            // Reflecting at both bounds is periodic with period 2 * width
+            double width = rightBound - leftBound;
+            double t = fmod(x - leftBound, 2.0 * width);
+            if (t < 0.0) {
+                t += 2.0 * width;
+            }
+            return t <= width ? leftBound + t : leftBound + 2.0 * width - t;
+        }
+        case DE_BOUNDARY_REINITIALIZE:
+            return leftBound + (rightBound - leftBound) * fmod(genRand(&pWorkspace->mt_rand), 1.0);
+        case DE_BOUNDARY_MIDPOINT:
+            return x < leftBound ? 0.5 * (parentX + leftBound) : 0.5 * (parentX + rightBound);
+        case DE_BOUNDARY_CLAMP:
+        default:
+            return x < leftBound ? leftBound : rightBound;
+    }
+}
+
+void de_mutate(pDeWorkspace_t pWorkspace, pDeOptimizationTarget_t pTarget, pDeConfig_t pConfig) {
+    for (uint32_t i = 0; i < pWorkspace->probe_population.size; i++) {
+        uint32_t donorCount = de_mutation_donor_count(pWorkspace->probe_population.strategy[i]);
+        uint32_t firstDiffDonor = donorCount % 2U; // with an odd donor count the first donor is the base vector
+        pVector_t pProbeVec = &pWorkspace->probe_population.members[i];
+        pVector_t pMainVec = &pWorkspace->main_population.members[i];
+        uint32_t* pDonors = &pWorkspace->donors[i * DE_MAX_DONORS];
+        double F = pWorkspace->probe_population.f[i];
+        for (uint32_t k = firstDiffDonor; k < donorCount; k += 2U) {
+            pVector_t pX2 = &pWorkspace->main_population.members[pDonors[k]];
+            pVector_t pX3 = de_population_or_archive_member(pWorkspace, pDonors[k + 1U]);
+            for (uint32_t j = 0; j < pProbeVec->num_dimensions; j++) {
+                if (de_variable_type(pTarget, j) != DE_VARIABLE_CATEGORICAL) {
+                    pProbeVec->coordinates[j] += F * (pX2->coordinates[j] - pX3->coordinates[j]);
+                } else if (pX2->coordinates[j] != pX3->coordinates[j] && fmod(genRand(&pWorkspace->mt_rand), 1.0) < F) {
+                    pProbeVec->coordinates[j] = de_scale_coordinate(pTarget, j, fmod(genRand(&pWorkspace->mt_rand), 1.0));
+                }
+            }
+        }
+        for (uint32_t j = 0; j < pProbeVec->num_dimensions; j++) {
+            double leftBound = pTarget->left_bounds[j];
+            double rightBound = pTarget->right_bounds[j];
+            pProbeVec->coordinates[j] = de_handle_boundary(pWorkspace, pConfig->boundary_handling, pProbeVec->coordinates[j], pMainVec->coordinates[j], leftBound, rightBound);
+            pProbeVec->coordinates[j] = de_repair_coordinate(pTarget, j, pProbeVec->coordinates[j]); // crossover only mixes in parent coordinates, which are valid already
+            if (pProbeVec->coordinates[j] < leftBound || pProbeVec->coordinates[j] > rightBound) {
+                fprintf(stderr, "[DE] Error: Coordinate out of bounds in mutation\n");
+            }
+        }
+    }
+}
+
+void de_crossover(pDeWorkspace_t pWorkspace, pDeConfig_t pConfig) {
+    for (uint32_t i = 0; i < pWorkspace->probe_population.size; i++) {
+        pVector_t pProbeVec = &pWorkspace->probe_population.members[i];
+        pVector_t pMainVec = &pWorkspace->main_population.members[i];
+        double CR = pWorkspace->probe_population.cr[i];
+
+        uint32_t d = genRandLong(&pWorkspace->mt_rand) % pProbeVec->num_dimensions;
+        switch (pConfig->crossover_type) {
+            case DE_CROSSOVER_EXPONENTIAL: {
+                // Keep the mutant coordinates d, d+1, ..., d+L-1 (mod D), inherit the rest from the parent
+                uint32_t L = 1U;
+                while (L < pProbeVec->num_dimensions && fmod(genRand(&pWorkspace->mt_rand), 1.0) < CR) {
+                    L++;
+                }
+                for (uint32_t k = L; k < pProbeVec->num_dimensions; k++) {
+                    uint32_t j = (d + k) % pProbeVec->num_dimensions;
+                    pProbeVec->coordinates[j] = pMainVec->coordinates[j];
+                }
+                break;
+            }
+            case DE_CROSSOVER_BINOMIAL:
+            default:
+                for (uint32_t j = 0; j < pProbeVec->num_dimensions; j++) {
+                    double r = fmod(genRand(&pWorkspace->mt_rand), 1.0);
+                    if (r < CR || j == d) {
+                        // do nothing
+                    } else {
+                        pProbeVec->coordinates[j] = pMainVec->coordinates[j];
+                    }
+                }
+                break;
+        }
+    }
+}
+
+/**
+ * @returns Euclidean distance between two vectors
+*/
+double de_distance(const double* pA, const double* pB, uint32_t numDimensions) {
+    double sum = 0.0;
+    for (uint32_t j = 0; j < numDimensions; j++) {
+        sum += (pA[j] - pB[j]) * (pA[j] - pB[j]);
+    }
+    return sqrt(sum);
+}
+
+/**
+ * @returns Index of the main population member nearest to `pVec`
+*/
+uint32_t de_nearest_member(pDeWorkspace_t pWorkspace, pVector_t pVec) {
+    uint32_t nearest = 0U;
+    double nearestDistance = INFINITY;
+    for (uint32_t k = 0; k < pWorkspace->main_population.size; k++) {
+        double distance = de_distance(pWorkspace->main_population.members[k].coordinates, pVec->coordinates, pVec->num_dimensions);
+        if (distance < nearestDistance) {
+            nearest = k;
+            nearestDistance = distance;
+        }
+    }
+    return nearest;
+}
+
+/**
+ * Evaluate the probes and let each replace its parent (with crowding: its nearest member) if it is better, parents are never re-evaluated as their fitness is cached
+*/
+void de_select(pDeWorkspace_t pWorkspace, pDeOptimizationTarget_t pTarget, pDeConfig_t pConfig, void* pUserData) {
+    de_evaluate_population(pWorkspace, pTarget, pConfig, &pWorkspace->probe_population, pUserData);
+    for (uint32_t i = 0; i < pWorkspace->main_population.size; i++) {
+        pVector_t pProbeVec = &pWorkspace->probe_population.members[i];
+        uint32_t rival = pConfig->selection == DE_SELECTION_CROWDING ? de_nearest_member(pWorkspace, pProbeVec) : i;
+        pVector_t pMainVec = &pWorkspace->main_population.members[rival];
+        de_mutation_strategy_t strategy = pWorkspace->probe_population.strategy[i];
+        uint32_t windowCell = pWorkspace->window_index * DE_MUTATION_STRATEGY_COUNT + strategy; // only meaningful for the ensembles
+        pWorkspace->strategy_trials[strategy]++;
+        if (de_is_better_member(pWorkspace, pConfig, &pWorkspace->probe_population, i, &pWorkspace->main_population, rival)) {
+            pWorkspace->strategy_successes[strategy]++;
+            if (de_is_ensemble(pConfig)) {
+                pWorkspace->window_successes[windowCell]++;
+                pWorkspace->window_success_cr[windowCell] += pWorkspace->probe_population.cr[i];
+            }
+            uint32_t k = pWorkspace->success_count++;
+            pWorkspace->success_f[k] = pWorkspace->probe_population.f[i];
+            pWorkspace->success_cr[k] = pWorkspace->probe_population.cr[i];
+            pWorkspace->success_weight[k] = pWorkspace->main_population.fitness[rival] - pWorkspace->probe_population.fitness[i];
+            if (!(pWorkspace->success_weight[k] > 0.0)) {
+                pWorkspace->success_weight[k] = pWorkspace->main_population.violation[rival] - pWorkspace->probe_population.violation[i]; // won on feasibility
+            }
+            if ((de_strategy_pool(pConfig) & DE_STRATEGY_BIT(DE_MUTATION_CURRENT_TO_PBEST_1)) != 0U) {
+                de_archive_parent(pWorkspace, pMainVec); // no other strategy reads the archive, filling it would only consume random numbers
+            }
+            for (uint32_t j = 0; j < pMainVec->num_dimensions; j++) {
+                pMainVec->coordinates[j] = pProbeVec->coordinates[j];
+            }
+            pWorkspace->main_population.fitness[rival] = pWorkspace->probe_population.fitness[i];
+            pWorkspace->main_population.violation[rival] = pWorkspace->probe_population.violation[i];
+            pWorkspace->main_population.f[rival] = pWorkspace->probe_population.f[i];
+            pWorkspace->main_population.cr[rival] = pWorkspace->probe_population.cr[i];
+            pWorkspace->main_population.strategy[rival] = strategy;
+        } else if (de_is_ensemble(pConfig)) {
+            pWorkspace->window_failures[windowCell]++;
+            if (pConfig->adaptation == DE_ADAPTATION_EPSDE) {
+                de_redraw_epsde_parameters(pWorkspace, rival); // the member the trial lost against, like the replacement above
+            }
+        }
+    }
+    de_workspace_set_best(pWorkspace, de_get_best(pWorkspace, pConfig, &pWorkspace->main_population)); // Let the workspace know who is the best
+}
+
+/**
+ * @returns The population size L-SHADE's linear reduction prescribes after the evaluations done so far
+*/
+uint32_t de_reduced_population_size(pDeWorkspace_t pWorkspace, pDeConfig_t pConfig) {
+    if (pConfig->final_population_size == 0U || pWorkspace->evaluation_count >= pConfig->reduction_evaluations) {
+        return pConfig->final_population_size == 0U ? pConfig->population_size : pConfig->final_population_size;
+    }
+    double progress = (double)pWorkspace->evaluation_count / (double)pConfig->reduction_evaluations;
+    return (uint32_t)round(pConfig->population_size - progress * (double)(pConfig->population_size - pConfig->final_population_size));
+}
+
+/**
+ * Drop the worst members until the population has the size prescribed by the linear reduction, the archive limit shrinks along
+*/
+void de_reduce_population(pDeWorkspace_t pWorkspace, pDeConfig_t pConfig) {
+    uint32_t newSize = de_reduced_population_size(pWorkspace, pConfig);
+    if (newSize >= pWorkspace->main_population.size) {
+        return;
+    }
+    pDePopulation_t pMain = &pWorkspace->main_population;
+    while (pMain->size > newSize) {
+        uint64_t worstIndex = 0U;
+        for (uint64_t i = 1; i < pMain->size; i++) {
+            if (de_is_better_member(pWorkspace, pConfig, pMain, worstIndex, pMain, i)) {
+                worstIndex = i;
+            }
+        }
+        de_population_swap_members(pMain, worstIndex, pMain->size - 1U);
+        pMain->size--;
+    }
+    pWorkspace->probe_population.size = newSize;
+    de_workspace_set_best(pWorkspace, de_get_best(pWorkspace, pConfig, pMain)); // the swaps may have moved the best member
+
+    pWorkspace->archive_capacity = (uint32_t)round(pConfig->archive_rate * newSize);
+    while (pWorkspace->archive.size > pWorkspace->archive_capacity) {
+        uint64_t victim = genRandLong(&pWorkspace->mt_rand) % pWorkspace->archive.size;
+        de_population_swap_members(&pWorkspace->archive, victim, pWorkspace->archive.size - 1U);
+        pWorkspace->archive.size--;
+    }
+}
+
+void de_record_generation(pDeWorkspace_t pWorkspace) {
+    if (pWorkspace->history_length == pWorkspace->history_capacity) {
+        pWorkspace->history_capacity = pWorkspace->history_capacity == 0U ? 64U : 2U * pWorkspace->history_capacity;
+        pWorkspace->history = realloc(pWorkspace->history, pWorkspace->history_capacity * sizeof(de_generation_stats_t));
+    }
+    double sum = 0.0;
+    for (uint32_t i = 0; i < pWorkspace->main_population.size; i++) {
+        sum += pWorkspace->main_population.fitness[i];
+    }
+    de_generation_stats_t* pStats = &pWorkspace->history[pWorkspace->history_length++];
+    pStats->best_fitness = pWorkspace->p_main_best_val;
+    pStats->mean_fitness = sum / (double)pWorkspace->main_population.size;
+}
+
+double de_elapsed_seconds(pDeWorkspace_t pWorkspace) {
+    struct timespec now;
+    timespec_get(&now, TIME_UTC);
+    return (double)(now.tv_sec - pWorkspace->start_time.tv_sec) + 1e-9 * (double)(now.tv_nsec - pWorkspace->start_time.tv_nsec);
+}
+
+/**
+ * @returns 1 if the single condition holds (and sets `*pReason`), otherwise 0
+*/
+uint8_t de_check_single_stop_condition(uint64_t iter_count, de_stop_condition_t* pCondition, pDeWorkspace_t pWorkspace, de_stop_reason_t* pReason) {
+    switch (pCondition->type) {
+        case STOP_AFTER_ITERS:
+            *pReason = DE_STOP_REASON_ITERS_EXHAUSTED;
+            return iter_count > pCondition->limitation.iters;
+        case STOP_WHEN_SATISFIED:
+            *pReason = DE_STOP_REASON_ACCURACY_REACHED;
+            return pWorkspace->p_main_best_val < pCondition->limitation.accuracy && pWorkspace->p_main_best_violation <= 0.0;
+        case STOP_AFTER_EVALUATIONS:
+            *pReason = DE_STOP_REASON_EVALUATIONS_EXHAUSTED;
+            return pWorkspace->evaluation_count >= pCondition->limitation.evaluations;
+        case STOP_ON_STAGNATION:
+            *pReason = DE_STOP_REASON_STAGNATED;
+            return iter_count - pWorkspace->last_improvement_iter >= pCondition->limitation.stagnation_iters;
+        case STOP_ON_CONVERGENCE: {
+            *pReason = DE_STOP_REASON_CONVERGED;
+            double minVal = pWorkspace->main_population.fitness[0];
+            double maxVal = minVal;
+            for (uint32_t i = 1; i < pWorkspace->main_population.size; i++) {
+                minVal = fmin(minVal, pWorkspace->main_population.fitness[i]);
+                maxVal = fmax(maxVal, pWorkspace->main_population.fitness[i]);
+            }
+            return maxVal - minVal <= pCondition->limitation.fitness_spread;
+        }
+        case STOP_AFTER_SECONDS:
+            *pReason = DE_STOP_REASON_TIME_LIMIT_REACHED;
+            return de_elapsed_seconds(pWorkspace) >= pCondition->limitation.seconds;
+        default:
+            return 1U; // unreachable after de_validate
+    }
+}
+
+/**
+ * @returns 1 if the combined stop conditions are satisfied (and sets `*pReason`), otherwise 0
+*/
+uint8_t de_check_stop_condition(uint64_t iter_count, pDeConfig_t pConfig, pDeWorkspace_t pWorkspace, de_stop_reason_t* pReason) {
+    if (de_is_better(pWorkspace, pConfig, pWorkspace->p_main_best_val, pWorkspace->p_main_best_violation, pWorkspace->stagnation_best_val, pWorkspace->stagnation_best_violation)) {
+        pWorkspace->stagnation_best_val = pWorkspace->p_main_best_val;
+        pWorkspace->stagnation_best_violation = pWorkspace->p_main_best_violation;
+        pWorkspace->last_improvement_iter = iter_count;
+    }
+
+    uint8_t isAll = pConfig->stop_combination == DE_STOP_WHEN_ALL;
+    uint8_t isStop = de_check_single_stop_condition(iter_count, &pConfig->stop_condition, pWorkspace, pReason);
+    de_stop_reason_t extraReason;
+    for (uint32_t k = 0; k < pConfig->extra_stop_condition_count && isStop == isAll; k++) {
+        isStop = de_check_single_stop_condition(iter_count, &pConfig->extra_stop_conditions[k], pWorkspace, &extraReason);
+        if (isStop && !isAll) {
+            *pReason = extraReason;
+        }
+    }
+    if (isStop) {
+        return 1U;
+    }
+    *pReason = DE_STOP_REASON_ITERS_EXHAUSTED;
+    return pConfig->max_generations != 0U && iter_count >= pConfig->max_generations;
+}
+
+/**
+ * @returns DE_STATUS_OK if the condition is well-formed, `*pIsBudget` is set for conditions that always hold eventually
+*/
+de_status_t de_validate_stop_condition(de_stop_condition_t* pCondition, uint8_t* pIsBudget) {
+    *pIsBudget = 0U;
+    switch (pCondition->type) {
+        case STOP_AFTER_ITERS:
+            *pIsBudget = 1U;
+            return DE_STATUS_OK;
+        case STOP_WHEN_SATISFIED:
+            return isnan(pCondition->limitation.accuracy) ? DE_STATUS_INVALID_STOP_CONDITION : DE_STATUS_OK;
+        case STOP_AFTER_EVALUATIONS:
+            *pIsBudget = 1U;
+            return pCondition->limitation.evaluations == 0U ? DE_STATUS_INVALID_STOP_CONDITION : DE_STATUS_OK;
+        case STOP_ON_STAGNATION:
+            return pCondition->limitation.stagnation_iters == 0U ? DE_STATUS_INVALID_STOP_CONDITION : DE_STATUS_OK;
+        case STOP_ON_CONVERGENCE:
+            return !(pCondition->limitation.fitness_spread >= 0.0) ? DE_STATUS_INVALID_STOP_CONDITION : DE_STATUS_OK;
+        case STOP_AFTER_SECONDS:
+            *pIsBudget = 1U;
+            return !(pCondition->limitation.seconds > 0.0 && isfinite(pCondition->limitation.seconds)) ? DE_STATUS_INVALID_STOP_CONDITION : DE_STATUS_OK;
+        default:
+            return DE_STATUS_INVALID_STOP_CONDITION;
+    }
+}
+
+/**
+ * `de_validate` apart from the objective, which the multi-objective targets keep elsewhere
+*/
+de_status_t de_validate_search(pDeOptimizationTarget_t pOptimizationTarget, pDeConfig_t pConfig) {
+    de_status_t status;
+    if (pOptimizationTarget == NULL || pConfig == NULL || pOptimizationTarget->left_bounds == NULL || pOptimizationTarget->right_bounds == NULL) {
+        return DE_STATUS_NULL_ARGUMENT;
+    }
+    if (pOptimizationTarget->g == NULL && (pOptimizationTarget->num_inequality_constraints > 0U || pOptimizationTarget->num_equality_constraints > 0U)) {
+        return DE_STATUS_NULL_ARGUMENT;
+    }
+    if (pOptimizationTarget->num_dimensions == 0U) {
+        return DE_STATUS_INVALID_DIMENSIONS;
+    }
+    if (pConfig->thread_count > 1U && !pOptimizationTarget->is_thread_safe && pOptimizationTarget->thread_user_data == NULL) {
+        return DE_STATUS_INVALID_THREAD_COUNT; // the objective would race on its user data
+    }
+    for (uint32_t j = 0; j < pOptimizationTarget->num_dimensions; j++) {
+        double leftBound = pOptimizationTarget->left_bounds[j];
+        double rightBound = pOptimizationTarget->right_bounds[j];
+        if (!isfinite(leftBound) || !isfinite(rightBound) || !(leftBound < rightBound)) {
+            return DE_STATUS_INVALID_BOUNDS;
+        }
+        de_variable_type_t type = de_variable_type(pOptimizationTarget, j);
+        if ((uint32_t)type > (uint32_t)DE_VARIABLE_CATEGORICAL || (type != DE_VARIABLE_CONTINUOUS && ceil(leftBound) > floor(rightBound))) {
+            return DE_STATUS_INVALID_VARIABLE_TYPES;
+        }
+    }
+    if ((uint32_t)pConfig->mutation_strategy > (uint32_t)DE_MUTATION_CURRENT_TO_PBEST_1 || (uint32_t)pConfig->crossover_type > (uint32_t)DE_CROSSOVER_EXPONENTIAL
+        || (uint32_t)pConfig->boundary_handling > (uint32_t)DE_BOUNDARY_MIDPOINT || (uint32_t)pConfig->selection > (uint32_t)DE_SELECTION_CROWDING) {
+        return DE_STATUS_INVALID_STRATEGY;
+    }
+    if ((uint32_t)pConfig->initialization > (uint32_t)DE_INIT_OPPOSITION
+        || (pConfig->initialization == DE_INIT_SOBOL && pOptimizationTarget->num_dimensions > DE_SOBOL_MAX_DIMENSIONS)) {
+        return DE_STATUS_INVALID_INITIALIZATION;
+    }
+    if ((uint32_t)pConfig->adaptation > (uint32_t)DE_ADAPTATION_EPSDE
+        || (de_is_ensemble(pConfig) && ((pConfig->strategy_pool >> DE_MUTATION_STRATEGY_COUNT) != 0U || pConfig->learning_period == 0U))
+        || (pConfig->adaptation == DE_ADAPTATION_JDE && !(pConfig->jde_tau1 >= 0.0 && pConfig->jde_tau1 <= 1.0 && pConfig->jde_tau2 >= 0.0 && pConfig->jde_tau2 <= 1.0))
+        || (pConfig->adaptation == DE_ADAPTATION_JADE && !(pConfig->adaptation_rate > 0.0 && pConfig->adaptation_rate <= 1.0))
+        || (pConfig->adaptation == DE_ADAPTATION_SHADE && pConfig->history_size == 0U)
+        || !(pConfig->archive_rate >= 0.0 && pConfig->archive_rate <= 10.0)) {
+        return DE_STATUS_INVALID_ADAPTATION;
+    }
+    if ((uint32_t)pConfig->constraint_handling > (uint32_t)DE_CONSTRAINTS_EPSILON || !(pConfig->equality_tolerance >= 0.0)
+        || (pConfig->constraint_handling == DE_CONSTRAINTS_PENALTY && !(pConfig->penalty_factor > 0.0 && isfinite(pConfig->penalty_factor)))
+        || (pConfig->constraint_handling == DE_CONSTRAINTS_EPSILON && (pConfig->epsilon_generations == 0U || !(pConfig->epsilon_exponent > 0.0 && isfinite(pConfig->epsilon_exponent))))) {
+        return DE_STATUS_INVALID_CONSTRAINT_HANDLING;
+    }
+    uint32_t pool = de_strategy_pool(pConfig);
+    if (pConfig->population_size <= de_pool_donor_count(pool)) {
+        return DE_STATUS_INVALID_POPULATION_SIZE; // de_draw_donors would never find enough distinct members
+    }
+    if (pConfig->final_population_size != 0U && (pConfig->final_population_size <= de_pool_donor_count(pool)
+        || pConfig->final_population_size > pConfig->population_size || pConfig->reduction_evaluations == 0U)) {
+        return DE_STATUS_INVALID_POPULATION_SIZE;
+    }
+    // Negated comparisons so that NaN is rejected too
+    if (!(pConfig->crossover_probability >= 0.0 && pConfig->crossover_probability <= 1.0)) {
+        return DE_STATUS_INVALID_CROSSOVER_PROBABILITY;
+    }
+    if (!(pConfig->amplification_factor >= 0.0 && pConfig->amplification_factor <= 2.0)) {
+        return DE_STATUS_INVALID_AMPLIFICATION_FACTOR;
+    }
+    if (!(pConfig->lambda >= 0.0 && pConfig->lambda <= 1.0)) {
+        return DE_STATUS_INVALID_LAMBDA;
+    }
+    if ((pool & DE_STRATEGY_BIT(DE_MUTATION_CURRENT_TO_PBEST_1)) != 0U && !(pConfig->p_best_fraction > 0.0 && pConfig->p_best_fraction <= 1.0)) {
+        return DE_STATUS_INVALID_P_BEST_FRACTION;
+    }
+    if (pOptimizationTarget->initial_population_size > 0U) {
+        if (pOptimizationTarget->initial_population == NULL || pOptimizationTarget->initial_population_size > pConfig->population_size) {
+            return DE_STATUS_INVALID_INITIAL_POPULATION;
+        }
+        for (uint32_t i = 0; i < pOptimizationTarget->initial_population_size; i++) {
+            for (uint32_t j = 0; j < pOptimizationTarget->num_dimensions; j++) {
+                double x = pOptimizationTarget->initial_population[i * pOptimizationTarget->num_dimensions + j];
+                if (!(x >= pOptimizationTarget->left_bounds[j] && x <= pOptimizationTarget->right_bounds[j]) || de_repair_coordinate(pOptimizationTarget, j, x) != x) {
+                    return DE_STATUS_INVALID_INITIAL_POPULATION;
+                }
+            }
+        }
+    }
+    if (pConfig->extra_stop_condition_count > DE_MAX_EXTRA_STOP_CONDITIONS || (uint32_t)pConfig->stop_combination > (uint32_t)DE_STOP_WHEN_ALL) {
+        return DE_STATUS_INVALID_STOP_CONDITION;
+    }
+    uint8_t isAll = pConfig->stop_combination == DE_STOP_WHEN_ALL;
+    uint8_t isBudget;
+    status = de_validate_stop_condition(&pConfig->stop_condition, &isBudget);
+    uint8_t isBounded = isBudget; // i.e. guaranteed to stop eventually
+    for (uint32_t k = 0; k < pConfig->extra_stop_condition_count && status == DE_STATUS_OK; k++) {
+        status = de_validate_stop_condition(&pConfig->extra_stop_conditions[k], &isBudget);
+        isBounded = isAll ? (isBounded && isBudget) : (isBounded || isBudget);
+    }
+    if (status != DE_STATUS_OK) {
+        return status;
+    }
+    if (!isBounded && pConfig->max_generations == 0U) {
+        return DE_STATUS_INVALID_STOP_CONDITION; // e.g. an accuracy that is never reached would spin forever
+    }
+    return DE_STATUS_OK;
+}
+
+de_status_t de_validate(pDeOptimizationTarget_t pOptimizationTarget, pDeConfig_t pConfig) {
+    if (pOptimizationTarget != NULL && pOptimizationTarget->f == NULL && pOptimizationTarget->f_batch == NULL) {
+        return DE_STATUS_NULL_ARGUMENT;
+    }
+    return de_validate_search(pOptimizationTarget, pConfig);
+}
+
+void de_result_free(pDeResult_t pResult) {
+    de_vector_free_coordinates(&pResult->best);
+    free(pResult->history);
+    pResult->history = NULL;
+    pResult->history_length = 0U;
+}
+
+/**
+ * Set v_0 of the epsilon constraint method to the violation of the member at 20% of the initial population sorted by violation
+*/
+void de_init_epsilon_level(pDeWorkspace_t pWorkspace) {
+    pDePopulation_t pMain = &pWorkspace->main_population;
+    double* sorted = malloc(pMain->size * sizeof(double));
+    for (uint32_t i = 0; i < pMain->size; i++) {
+        uint32_t j = i;
+        for (; j > 0 && sorted[j - 1] > pMain->violation[i]; j--) {
+            sorted[j] = sorted[j - 1];
+        }
+        sorted[j] = pMain->violation[i];
+    }
+    pWorkspace->epsilon_initial = sorted[(uint32_t)(0.2 * (double)pMain->size)];
+    pWorkspace->epsilon_level = pWorkspace->epsilon_initial;
+    free(sorted);
+}
+
+/**
+ * Lower the epsilon level for generation `iter_count` + 1: v_0 (1 - t / T_c)^cp, 0 from T_c on
+*/
+void de_update_epsilon_level(pDeWorkspace_t pWorkspace, pDeConfig_t pConfig, uint64_t iter_count) {
+    if (pConfig->constraint_handling != DE_CONSTRAINTS_EPSILON) {
+        return;
+    }
+    pWorkspace->epsilon_level = iter_count >= pConfig->epsilon_generations ? 0.0
+        : pWorkspace->epsilon_initial * pow(1.0 - (double)iter_count / (double)pConfig->epsilon_generations, pConfig->epsilon_exponent);
+}
+
+struct de_state_s {
+    de_optimization_target_t target; // the warm-start rows are dropped after de_init
+    de_config_t config;
+    void* p_user_data;
+    de_workspace_t workspace;
+    uint64_t iter_count;
+    uint8_t is_stopped; de_stop_reason_t stop_reason; // of the last de_step
+};
+
+de_status_t de_init(pDeOptimizationTarget_t pOptimizationTarget, pDeConfig_t pConfig, void* pUserData, de_state_t** ppState) {
+    if (ppState == NULL) {
+        return DE_STATUS_NULL_ARGUMENT;
+    }
+    *ppState = NULL;
+    de_status_t status = de_validate(pOptimizationTarget, pConfig);
+    if (status != DE_STATUS_OK) {
+        return status;
+    }
+
+    pDeState_t pState = malloc(sizeof(de_state_t));
+    pState->target = *pOptimizationTarget;
+    pState->config = *pConfig;
+    pState->p_user_data = pUserData;
+    pState->iter_count = 0U;
+    pState->is_stopped = 0U;
+    pState->stop_reason = DE_STOP_REASON_CANCELLED;
+
+    pDeWorkspace_t pWorkspace = &pState->workspace;
+    de_workspace_init (pWorkspace, &pState->config, pState->target.num_dimensions);
+    de_generate_main_population (pWorkspace, &pState->target, &pState->config);
+    de_evaluate_population (pWorkspace, &pState->target, &pState->config, &pWorkspace->main_population, pUserData);
+    if (pState->config.initialization == DE_INIT_OPPOSITION) {
+        de_apply_opposition (pWorkspace, &pState->target, &pState->config, pUserData);
+    }
+    de_init_epsilon_level (pWorkspace);
+    de_workspace_set_best (pWorkspace, de_get_best (pWorkspace, &pState->config, &pWorkspace->main_population));
+    pState->target.initial_population = NULL;
+    pState->target.initial_population_size = 0U;
+
+    *ppState = pState;
+    return DE_STATUS_OK;
+}
+
+uint8_t de_step(pDeState_t pState) {
+    pDeWorkspace_t pWorkspace = &pState->workspace;
+    pDeConfig_t pConfig = &pState->config;
+    de_update_epsilon_level (pWorkspace, pConfig, pState->iter_count);
+    de_workspace_set_best (pWorkspace, de_get_best (pWorkspace, pConfig, &pWorkspace->main_population)); // the order changes with the epsilon level or a new config
+    de_sample_parameters (pWorkspace, pConfig);
+    de_reproduce (pWorkspace, &pState->target, pConfig);
+    de_mutate (pWorkspace, &pState->target, pConfig);
+    de_crossover (pWorkspace, pConfig);
+    de_select (pWorkspace, &pState->target, pConfig, pState->p_user_data);
+    de_adapt_parameters (pWorkspace, pConfig);
+    de_reduce_population (pWorkspace, pConfig);
+    pState->iter_count++;
+    if (pConfig->record_history) {
+        de_record_generation (pWorkspace);
+    }
+    pState->is_stopped = de_check_stop_condition(pState->iter_count, pConfig, pWorkspace, &pState->stop_reason);
+    if (pConfig->progress_callback != NULL) {
+        de_population_view_t view = {
+            .members = pWorkspace->main_population.members,
+            .fitness = pWorkspace->main_population.fitness,
+            .size = (uint32_t)pWorkspace->main_population.size
+        };
+        if (pConfig->progress_callback(pState->iter_count, pWorkspace->p_main_best_val, &view, pConfig->progress_user_data) != 0 && !pState->is_stopped) {
+            pState->is_stopped = 1U;
+            pState->stop_reason = DE_STOP_REASON_CANCELLED;
+        }
+    }
+    return pState->is_stopped;
+}
+
+double de_best(pDeState_t pState, double* pCoordinates) {
+    if (pCoordinates != NULL) {
+        for (uint32_t j = 0; j < pState->target.num_dimensions; j++) {
+            pCoordinates[j] = pState->workspace.p_main_best->coordinates[j];
+        }
+    }
+    return pState->workspace.p_main_best_val;
+}
+
+double de_best_violation(pDeState_t pState) {
+    return pState->workspace.p_main_best_violation;
+}
+
+uint32_t de_population_size(pDeState_t pState) {
+    return (uint32_t)pState->workspace.main_population.size;
+}
+
+void de_population(pDeState_t pState, double* pCoordinates, double* pFitness) {
+    pDePopulation_t pMain = &pState->workspace.main_population;
+    uint32_t numDimensions = pState->target.num_dimensions;
+    for (uint32_t i = 0; i < pMain->size; i++) {
+        if (pCoordinates != NULL) {
+            for (uint32_t j = 0; j < numDimensions; j++) {
+                pCoordinates[i * numDimensions + j] = pMain->members[i].coordinates[j];
+            }
+        }
+        if (pFitness != NULL) {
+            pFitness[i] = pMain->fitness[i];
+        }
+    }
+}
+
+uint64_t de_generations(pDeState_t pState) {
+    return pState->iter_count;
+}
+
+uint64_t de_evaluations(pDeState_t pState) {
+    return pState->workspace.evaluation_count;
+}
+
+de_status_t de_set_config(pDeState_t pState, pDeConfig_t pConfig) {
+    if (pConfig == NULL) {
+        return DE_STATUS_NULL_ARGUMENT;
+    }
+    de_status_t status = de_validate(&pState->target, pConfig);
+    if (status != DE_STATUS_OK) {
+        return status;
+    }
+    // These size the workspace buffers
+    if (pConfig->population_size != pState->config.population_size || pConfig->final_population_size != pState->config.final_population_size
+        || pConfig->adaptation != pState->config.adaptation || pConfig->history_size != pState->config.history_size
+        || pConfig->archive_rate != pState->config.archive_rate
+        || (de_is_ensemble(pConfig) && (pConfig->learning_period != pState->config.learning_period || de_strategy_pool(pConfig) != de_strategy_pool(&pState->config)))) {
+        return DE_STATUS_INVALID_CONFIG_CHANGE;
+    }
+    if (pConfig->thread_count != pState->config.thread_count) {
+        de_workspace_start_workers(&pState->workspace, pConfig->thread_count);
+    }
+    pState->config = *pConfig;
+    return DE_STATUS_OK;
+}
+
+void de_finish(pDeState_t pState, pDeResult_t pResult) {
+    pDeWorkspace_t pWorkspace = &pState->workspace;
+    if (pResult != NULL) {
+        pVector_t pBest = pWorkspace->p_main_best;
+        *pResult = (de_result_t){
+            .best = { .num_dimensions = pBest->num_dimensions },
+            .best_fitness = pWorkspace->p_main_best_val,
+            .generations = pState->iter_count,
+            .evaluations = pWorkspace->evaluation_count,
+            .stop_reason = pState->is_stopped ? pState->stop_reason : DE_STOP_REASON_CANCELLED,
+            .history = pWorkspace->history,
+            .history_length = pWorkspace->history_length,
+            .best_violation = pWorkspace->p_main_best_violation,
+            .is_feasible = pWorkspace->p_main_best_violation <= 0.0
+        };
+        for (uint32_t k = 0; k < DE_MUTATION_STRATEGY_COUNT; k++) {
+            pResult->strategy_trials[k] = pWorkspace->strategy_trials[k];
+            pResult->strategy_successes[k] = pWorkspace->strategy_successes[k];
+        }
+        pWorkspace->history = NULL; // ownership moves to the result
+        de_vector_allocate_coordinates (&pResult->best);
+        for (uint32_t i = 0; i < pResult->best.num_dimensions; i++) {
+            pResult->best.coordinates[i] = pBest->coordinates[i];
+        }
+    }
+
+    de_workspace_deinit (pWorkspace);
+    free(pState);
+}
+
+void de_optima_free(pDeOptima_t pOptima) {
+    free(pOptima->coordinates);
+    free(pOptima->fitness);
+    free(pOptima->violation);
+    pOptima->coordinates = NULL;
+    pOptima->fitness = NULL;
+    pOptima->violation = NULL;
+    pOptima->size = 0U;
+}
+
+de_status_t de_distinct_optima(pDeState_t pState, double radius, pDeOptima_t pOptima) {
+    if (pState == NULL || pOptima == NULL) {
+        return DE_STATUS_NULL_ARGUMENT;
+    }
+    if (!(radius >= 0.0 && isfinite(radius))) {
+        return DE_STATUS_INVALID_NICHE_RADIUS;
+    }
+    pDeWorkspace_t pWorkspace = &pState->workspace;
+    pDePopulation_t pMain = &pWorkspace->main_population;
+    uint32_t numDimensions = pState->target.num_dimensions;
+    *pOptima = (de_optima_t){
+        .size = 0U,
+        .num_dimensions = numDimensions,
+        .coordinates = malloc(pMain->size * numDimensions * sizeof(double)),
+        .fitness = malloc(pMain->size * sizeof(double)),
+        .violation = malloc(pMain->size * sizeof(double)),
+        .generations = pState->iter_count,
+        .evaluations = pWorkspace->evaluation_count,
+        .stop_reason = pState->is_stopped ? pState->stop_reason : DE_STOP_REASON_CANCELLED
+    };
+    de_rank_main_population(pWorkspace, &pState->config);
+    for (uint32_t r = 0; r < pMain->size; r++) {
+        pVector_t pMember = &pMain->members[pWorkspace->ranking[r]];
+        uint8_t isDuplicate = 0U;
+        for (uint32_t k = 0; k < pOptima->size && !isDuplicate; k++) {
+            isDuplicate = de_distance(&pOptima->coordinates[k * numDimensions], pMember->coordinates, numDimensions) <= radius;
+        }
+        if (isDuplicate) {
+            continue;
+        }
+        for (uint32_t j = 0; j < numDimensions; j++) {
+            pOptima->coordinates[pOptima->size * numDimensions + j] = pMember->coordinates[j];
+        }
+        pOptima->fitness[pOptima->size] = pMain->fitness[pWorkspace->ranking[r]];
+        pOptima->violation[pOptima->size] = pMain->violation[pWorkspace->ranking[r]];
+        pOptima->size++;
+    }
+    return DE_STATUS_OK;
+}
+
+de_status_t de_minimum(de_optimization_target_t* pOptimizationTarget, de_config_t* pConfig, void* pUserData, pDeResult_t pResult) {
+    if (pResult == NULL) {
+        return DE_STATUS_NULL_ARGUMENT;
+    }
+    *pResult = (de_result_t){ .best = { .coordinates = NULL, .num_dimensions = 0U }, .history = NULL };
+    pDeState_t pState;
+    de_status_t status = de_init(pOptimizationTarget, pConfig, pUserData, &pState);
+    if (status != DE_STATUS_OK) {
+        return status;
+    }
+    while (!de_step(pState)) {
+    }
+    de_finish(pState, pResult);
+
+    return DE_STATUS_OK;
+}
+
+/**
+ * @returns 1 if objective vector `pA` is nowhere worse than `pB`
+*/
+uint8_t de_weakly_dominates(const double* pA, const double* pB, uint32_t numObjectives) {
+    for (uint32_t m = 0; m < numObjectives; m++) {
+        if (!(pA[m] <= pB[m])) {
+            return 0U;
+        }
+    }
+    return 1U;
+}
+
+/**
+ * @returns 1 if objective vector `pA` is nowhere worse than `pB` and better in at least one objective
+*/
+uint8_t de_dominates(const double* pA, const double* pB, uint32_t numObjectives) {
+    return de_weakly_dominates(pA, pB, numObjectives) && !de_weakly_dominates(pB, pA, numObjectives);
+}
+
+/**
+ * Non-dominated sorting: `pRanks[i]` becomes the index of the front of row i of `pObjectives`, 0 for the non-dominated rows
+*/
+void de_pareto_ranks(const double* pObjectives, uint32_t count, uint32_t numObjectives, uint32_t* pRanks) {
+    for (uint32_t i = 0; i < count; i++) {
+        pRanks[i] = UINT32_MAX;
+    }
+    uint32_t rankedCount = 0U;
+    for (uint32_t rank = 0; rankedCount < count; rank++) {
+        // Peel off one front at a time, quadratic in the population size which stays small
+        for (uint32_t i = 0; i < count; i++) {
+            if (pRanks[i] != UINT32_MAX) {
+                continue;
+            }
+            uint8_t isDominated = 0U;
+            for (uint32_t k = 0; k < count && !isDominated; k++) {
+                isDominated = (pRanks[k] == UINT32_MAX || pRanks[k] == rank) && de_dominates(&pObjectives[k * numObjectives], &pObjectives[i * numObjectives], numObjectives);
+            }
+            if (!isDominated) {
+                pRanks[i] = rank;
+                rankedCount++;
+            }
+        }
+    }
+}
+
+/**
+ * NSGA-II crowding distance of the `frontSize` rows `pFront` of `pObjectives` into `pDistances`, infinite for the extremes of every objective
+*/
+void de_crowding_distances(const double* pObjectives, uint32_t numObjectives, const uint32_t* pFront, uint32_t frontSize, double* pDistances) {
+    uint32_t* sorted = malloc(frontSize * sizeof(uint32_t)); // positions within the front
+    for (uint32_t k = 0; k < frontSize; k++) {
+        pDistances[k] = 0.0;
+    }
+    for (uint32_t m = 0; m < numObjectives; m++) {
+        for (uint32_t k = 0; k < frontSize; k++) {
+            uint32_t l = k;
+            for (; l > 0 && pObjectives[pFront[sorted[l - 1]] * numObjectives + m] > pObjectives[pFront[k] * numObjectives + m]; l--) {
+                sorted[l] = sorted[l - 1];
+            }
+            sorted[l] = k;
+        }
+        double lowest = pObjectives[pFront[sorted[0]] * numObjectives + m];
+        double highest = pObjectives[pFront[sorted[frontSize - 1U]] * numObjectives + m];
+        pDistances[sorted[0]] = INFINITY;
+        pDistances[sorted[frontSize - 1U]] = INFINITY;
+        if (!(highest > lowest)) {
+            continue;
+        }
+        for (uint32_t k = 1; k + 1U < frontSize; k++) {
+            double gap = pObjectives[pFront[sorted[k + 1U]] * numObjectives + m] - pObjectives[pFront[sorted[k - 1U]] * numObjectives + m];
+            pDistances[sorted[k]] += gap / (highest - lowest);
+        }
+    }
+    free(sorted);
+}
+
+/**
+ * Cut the main population back to `keepCount` members: whole fronts from the first on, the front that does not fit by the largest crowding distance,
+ * the survivors are moved to the front of the population together with their rows of `pObjectives`
+*/
+void de_truncate_pareto_population(pDeWorkspace_t pWorkspace, double* pObjectives, uint32_t numObjectives, uint32_t keepCount) {
+    pDePopulation_t pMain = &pWorkspace->main_population;
+    uint32_t count = (uint32_t)pMain->size;
+    uint32_t* ranks = malloc(count * sizeof(uint32_t));
+    uint32_t* front = malloc(count * sizeof(uint32_t));
+    double* distances = malloc(count * sizeof(double));
+    uint8_t* isKept = calloc(count, sizeof(uint8_t));
+    de_pareto_ranks(pObjectives, count, numObjectives, ranks);
+    uint32_t keptCount = 0U;
+    for (uint32_t rank = 0; keptCount < keepCount; rank++) {
+        uint32_t frontSize = 0U;
+        for (uint32_t i = 0; i < count; i++) {
+            if (ranks[i] == rank) {
+                front[frontSize++] = i;
+            }
+        }
+        if (keptCount + frontSize > keepCount) {
+            // Most crowded last, the survivors are the first ones after sorting by decreasing distance
+            de_crowding_distances(pObjectives, numObjectives, front, frontSize, distances);
+            for (uint32_t k = 1; k < frontSize; k++) {
+                uint32_t member = front[k]; double distance = distances[k];
+                uint32_t l = k;
+                for (; l > 0 && distances[l - 1] < distance; l--) {
+                    front[l] = front[l - 1]; distances[l] = distances[l - 1];
+                }
+                front[l] = member; distances[l] = distance;
+            }
+            frontSize = keepCount - keptCount;
+        }
+        for (uint32_t k = 0; k < frontSize; k++) {
+            isKept[front[k]] = 1U;
+        }
+        keptCount += frontSize;
+    }
+
+    uint32_t last = count;
+    for (uint32_t slot = 0; slot < keepCount; slot++) {
+        if (isKept[slot]) {
+            continue;
+        }
+        do {
+            last--;
+        } while (!isKept[last]);
+        de_population_swap_members(pMain, slot, last);
+        for (uint32_t m = 0; m < numObjectives; m++) {
+            double objective = pObjectives[slot * numObjectives + m];
+            pObjectives[slot * numObjectives + m] = pObjectives[last * numObjectives + m];
+            pObjectives[last * numObjectives + m] = objective;
+        }
+        isKept[last] = 0U;
+    }
+    pMain->size = keepCount;
+    free(isKept);
+    free(distances);
+    free(front);
+    free(ranks);
+}
+
+/**
+ * Evaluate all members of the population into the rows of `pObjectives`, the first objective doubles as the cached fitness
+*/
+void de_evaluate_objectives(pDeWorkspace_t pWorkspace, pDeMultiOptimizationTarget_t pTarget, pDePopulation_t pPopulation, double* pObjectives, void* pUserData) {
+    pWorkspace->evaluation_count += pPopulation->size;
+    for (uint32_t i = 0; i < pPopulation->size; i++) {
+        pTarget->f(pPopulation->members[i], &pObjectives[i * pTarget->num_objectives], pUserData);
+        pPopulation->fitness[i] = pObjectives[i * pTarget->num_objectives];
+    }
+}
+
+/**
+ * GDE3 selection: a trial replaces the parent it weakly dominates, is dropped if the parent weakly dominates it and joins the population otherwise
+*/
+void de_pareto_select(pDeWorkspace_t pWorkspace, double* pObjectives, const double* pTrialObjectives, uint32_t numObjectives) {
+    pDePopulation_t pMain = &pWorkspace->main_population;
+    pDePopulation_t pProbes = &pWorkspace->probe_population;
+    for (uint32_t i = 0; i < pProbes->size; i++) {
+        const double* pTrial = &pTrialObjectives[i * numObjectives];
+        uint64_t slot;
+        if (de_weakly_dominates(pTrial, &pObjectives[i * numObjectives], numObjectives)) {
+            slot = i;
+        } else if (de_weakly_dominates(&pObjectives[i * numObjectives], pTrial, numObjectives)) {
+            continue;
+        } else {
+            slot = pMain->size++;
+        }
+        for (uint32_t j = 0; j < pMain->members[slot].num_dimensions; j++) {
+            pMain->members[slot].coordinates[j] = pProbes->members[i].coordinates[j];
+        }
+        for (uint32_t m = 0; m < numObjectives; m++) {
+            pObjectives[slot * numObjectives + m] = pTrial[m];
+        }
+        pMain->fitness[slot] = pProbes->fitness[i];
+    }
+}
+
+/**
+ * @returns The single-objective target searching the same space as `pTarget`, without an objective
+*/
+de_optimization_target_t de_multi_search_target(pDeMultiOptimizationTarget_t pTarget) {
+    return (de_optimization_target_t){
+        .num_dimensions = pTarget->num_dimensions,
+        .left_bounds = pTarget->left_bounds,
+        .right_bounds = pTarget->right_bounds,
+        .variable_types = pTarget->variable_types
+    };
+}
+
+/**
+ * @returns `*pConfig` reduced to what a multi-objective run uses: DE/rand/1 without adaptation, archive, reduction, crowding, threads or callbacks
+*/
+de_config_t de_multi_search_config(pDeConfig_t pConfig) {
+    de_config_t config = *pConfig;
+    config.mutation_strategy = DE_MUTATION_RAND_1;
+    config.adaptation = DE_ADAPTATION_NONE;
+    config.archive_rate = 0.0;
+    config.final_population_size = 0U;
+    config.constraint_handling = DE_CONSTRAINTS_FEASIBILITY_RULES;
+    config.selection = DE_SELECTION_PARENT;
+    config.thread_count = 1U;
+    config.record_history = 0U;
+    config.progress_callback = NULL;
+    return config;
+}
+
+de_status_t de_validate_multi(pDeMultiOptimizationTarget_t pOptimizationTarget, pDeConfig_t pConfig) {
+    if (pOptimizationTarget == NULL || pConfig == NULL || pOptimizationTarget->f == NULL) {
+        return DE_STATUS_NULL_ARGUMENT;
+    }
+    if (pOptimizationTarget->num_objectives == 0U) {
+        return DE_STATUS_INVALID_DIMENSIONS;
+    }
+    if (pConfig->initialization == DE_INIT_OPPOSITION) {
+        return DE_STATUS_INVALID_INITIALIZATION; // it ranks the members by a single fitness
+    }
+    // Without a single fitness there is nothing to be accurate, stagnant or converged about
+    uint8_t isBudget = pConfig->stop_condition.type == STOP_AFTER_ITERS || pConfig->stop_condition.type == STOP_AFTER_EVALUATIONS || pConfig->stop_condition.type == STOP_AFTER_SECONDS;
+    for (uint32_t k = 0; k < pConfig->extra_stop_condition_count && k < DE_MAX_EXTRA_STOP_CONDITIONS; k++) {
+        de_stop_type_t type = pConfig->extra_stop_conditions[k].type;
+        isBudget = isBudget && (type == STOP_AFTER_ITERS || type == STOP_AFTER_EVALUATIONS || type == STOP_AFTER_SECONDS);
+    }
+    if (!isBudget) {
+        return DE_STATUS_INVALID_STOP_CONDITION;
+    }
+    de_optimization_target_t target = de_multi_search_target(pOptimizationTarget);
+    de_config_t config = de_multi_search_config(pConfig);
+    return de_validate_search(&target, &config);
+}
+
+void de_pareto_front_free(pDeParetoFront_t pFront) {
+    free(pFront->coordinates);
+    free(pFront->objectives);
+    pFront->coordinates = NULL;
+    pFront->objectives = NULL;
+    pFront->size = 0U;
+}
+
+de_status_t de_pareto_minimum(pDeMultiOptimizationTarget_t pOptimizationTarget, pDeConfig_t pConfig, void* pUserData, pDeParetoFront_t pFront) {
+    if (pFront == NULL) {
+        return DE_STATUS_NULL_ARGUMENT;
+    }
+    *pFront = (de_pareto_front_t){ .coordinates = NULL, .objectives = NULL };
+    de_status_t status = de_validate_multi(pOptimizationTarget, pConfig);
+    if (status != DE_STATUS_OK) {
+        return status;
+    }
+
+    uint32_t populationSize = pConfig->population_size;
+    uint32_t numDimensions = pOptimizationTarget->num_dimensions;
+    uint32_t numObjectives = pOptimizationTarget->num_objectives;
+    de_optimization_target_t target = de_multi_search_target(pOptimizationTarget);
+    de_config_t config = de_multi_search_config(pConfig);
+    config.population_size = 2U * populationSize; // room for the trials that join the population next to their parent
+    de_workspace_t workspace;
+    de_workspace_init (&workspace, &config, numDimensions);
+    config.population_size = populationSize;
+    workspace.main_population.size = populationSize;
+    workspace.probe_population.size = populationSize;
+    double* objectives = malloc(2U * populationSize * numObjectives * sizeof(double)); // one row per main population member
+    double* trialObjectives = malloc(populationSize * numObjectives * sizeof(double));
+
+    de_generate_main_population (&workspace, &target, &config);
+    de_evaluate_objectives (&workspace, pOptimizationTarget, &workspace.main_population, objectives, pUserData);
+    de_workspace_set_best (&workspace, 0U); // DE/rand/1 has no use for a best member, the stop conditions only need it to be set
+    uint64_t iterCount = 0U;
+    de_stop_reason_t stopReason;
+    do {
+        de_sample_parameters (&workspace, &config);
+        de_reproduce (&workspace, &target, &config);
+        de_mutate (&workspace, &target, &config);
+        de_crossover (&workspace, &config);
+        de_evaluate_objectives (&workspace, pOptimizationTarget, &workspace.probe_population, trialObjectives, pUserData);
+        de_pareto_select (&workspace, objectives, trialObjectives, numObjectives);
+        if (workspace.main_population.size > populationSize) {
+            de_truncate_pareto_population (&workspace, objectives, numObjectives, populationSize);
+        }
+        de_workspace_set_best (&workspace, 0U);
+        iterCount++;
+    } while (!de_check_stop_condition(iterCount, &config, &workspace, &stopReason));
+
+    // The non-dominated members, sorted by the first objective
+    uint32_t* ranks = malloc(populationSize * sizeof(uint32_t));
+    uint32_t* front = malloc(populationSize * sizeof(uint32_t));
+    de_pareto_ranks(objectives, populationSize, numObjectives, ranks);
+    uint32_t frontSize = 0U;
+    for (uint32_t i = 0; i < populationSize; i++) {
+        if (ranks[i] != 0U) {
+            continue;
+        }
+        uint32_t k = frontSize++;
+        for (; k > 0 && objectives[front[k - 1] * numObjectives] > objectives[i * numObjectives]; k--) {
+            front[k] = front[k - 1];
+        }
+        front[k] = i;
+    }
+    *pFront = (de_pareto_front_t){
+        .size = frontSize,
+        .num_dimensions = numDimensions,
+        .num_objectives = numObjectives,
+        .coordinates = malloc(frontSize * numDimensions * sizeof(double)),
+        .objectives = malloc(frontSize * numObjectives * sizeof(double)),
+        .generations = iterCount,
+        .evaluations = workspace.evaluation_count,
+        .stop_reason = stopReason
+    };
+    for (uint32_t k = 0; k < frontSize; k++) {
+        for (uint32_t j = 0; j < numDimensions; j++) {
+            pFront->coordinates[k * numDimensions + j] = workspace.main_population.members[front[k]].coordinates[j];
+        }
+        for (uint32_t m = 0; m < numObjectives; m++) {
+            pFront->objectives[k * numObjectives + m] = objectives[front[k] * numObjectives + m];
+        }
+    }
+
+    free(front);
+    free(ranks);
+    free(trialObjectives);
+    free(objectives);
+    de_workspace_deinit (&workspace);
+    return DE_STATUS_OK;
+}
+
+typedef struct {
+    pDeState_t pState;
+    uint32_t generationCount;
+} de_island_epoch_t;
+
+/**
+ * Step one island through a migration interval, or until its stop conditions hold
+*/
+void* de_run_island_epoch(void* pArg) {
+    de_island_epoch_t* pEpoch = pArg;
+    for (uint32_t g = 0; g < pEpoch->generationCount; g++) {
+        if (de_step(pEpoch->pState)) {
+            break;
+        }
+    }
+    return NULL;
+}
+
+/**
+ * @returns The number of islands sending migrants to every island
+*/
+uint32_t de_immigration_sources(pDeIslandConfig_t pIslandConfig) {
+    if (pIslandConfig->island_count <= 1U) {
+        return 0U;
+    }
+    return pIslandConfig->topology == DE_MIGRATION_RING ? 1U : pIslandConfig->island_count - 1U;
+}
+
+/**
+ * Copy the `migration_count` best members of every island over the worst members of the islands it sends to,
+ * all emigrants are taken before any island receives
+*/
+void de_migrate(pDeState_t* pIslands, pDeIslandConfig_t pIslandConfig) {
+    uint32_t islandCount = pIslandConfig->island_count;
+    uint32_t migrationCount = pIslandConfig->migration_count;
+    uint32_t numDimensions = pIslands[0]->target.num_dimensions;
+    uint32_t emigrantCount = islandCount * migrationCount;
+    double* coordinates = malloc(emigrantCount * numDimensions * sizeof(double));
+    double* cached = malloc(emigrantCount * 4U * sizeof(double)); // fitness, violation, F and CR of every emigrant
+    de_mutation_strategy_t* strategies = malloc(emigrantCount * sizeof(de_mutation_strategy_t));
+    for (uint32_t i = 0; i < islandCount; i++) {
+        pDeWorkspace_t pWorkspace = &pIslands[i]->workspace;
+        pDePopulation_t pMain = &pWorkspace->main_population;
+        de_rank_main_population(pWorkspace, &pIslands[i]->config);
+        for (uint32_t k = 0; k < migrationCount; k++) {
+            uint32_t member = pWorkspace->ranking[k];
+            uint32_t row = i * migrationCount + k;
+            for (uint32_t j = 0; j < numDimensions; j++) {
+                coordinates[row * numDimensions + j] = pMain->members[member].coordinates[j];
+            }
+            cached[row * 4U] = pMain->fitness[member];
+            cached[row * 4U + 1U] = pMain->violation[member];
+            cached[row * 4U + 2U] = pMain->f[member];
+            cached[row * 4U + 3U] = pMain->cr[member];
+            strategies[row] = pMain->strategy[member];
+        }
+    }
+    for (uint32_t i = 0; i < islandCount; i++) {
+        pDeWorkspace_t pWorkspace = &pIslands[i]->workspace;
+        pDePopulation_t pMain = &pWorkspace->main_population;
+        de_rank_main_population(pWorkspace, &pIslands[i]->config);
+        uint32_t worst = (uint32_t)pMain->size; // immigrants replace ranking[worst - 1], ranking[worst - 2], ...
+        for (uint32_t source = 0; source < islandCount; source++) {
+            uint8_t isSending = pIslandConfig->topology == DE_MIGRATION_RING ? (source + 1U) % islandCount == i : source != i;
+            if (!isSending) {
+                continue;
+            }
+            for (uint32_t k = 0; k < migrationCount; k++) {
+                uint32_t member = pWorkspace->ranking[--worst];
+                uint32_t row = source * migrationCount + k;
+                for (uint32_t j = 0; j < numDimensions; j++) {
+                    pMain->members[member].coordinates[j] = coordinates[row * numDimensions + j];
+                }
+                pMain->fitness[member] = cached[row * 4U];
+                pMain->violation[member] = cached[row * 4U + 1U];
+                pMain->f[member] = cached[row * 4U + 2U];
+                pMain->cr[member] = cached[row * 4U + 3U];
+                pMain->strategy[member] = strategies[row];
+            }
+        }
+        de_workspace_set_best(pWorkspace, de_get_best(pWorkspace, &pIslands[i]->config, pMain));
+    }
+    free(strategies);
+    free(cached);
+    free(coordinates);
+}
+
+de_status_t de_validate_islands(pDeOptimizationTarget_t pOptimizationTarget, pDeConfig_t pConfig, pDeIslandConfig_t pIslandConfig) {
+    if (pIslandConfig == NULL) {
+        return DE_STATUS_NULL_ARGUMENT;
+    }
+    de_status_t status = de_validate(pOptimizationTarget, pConfig);
+    if (status != DE_STATUS_OK) {
+        return status;
+    }
+    if (pIslandConfig->island_count == 0U || pIslandConfig->migration_interval == 0U || (uint32_t)pIslandConfig->topology > (uint32_t)DE_MIGRATION_FULLY_CONNECTED) {
+        return DE_STATUS_INVALID_ISLAND_CONFIG;
+    }
+    uint32_t smallestSize = pConfig->final_population_size != 0U ? pConfig->final_population_size : pConfig->population_size;
+    if ((uint64_t)de_immigration_sources(pIslandConfig) * pIslandConfig->migration_count >= smallestSize) {
+        return DE_STATUS_INVALID_ISLAND_CONFIG; // the immigrants would replace the island's best member too
+    }
+    if (pIslandConfig->island_count > 1U && (pConfig->thread_count > 1U || (!pOptimizationTarget->is_thread_safe && pOptimizationTarget->thread_user_data == NULL))) {
+        return DE_STATUS_INVALID_THREAD_COUNT;
+    }
+    return DE_STATUS_OK;
+}
+
+de_status_t de_island_minimum(pDeOptimizationTarget_t pOptimizationTarget, pDeConfig_t pConfig, pDeIslandConfig_t pIslandConfig, void* pUserData, pDeResult_t pResult) {
+    if (pResult == NULL) {
+        return DE_STATUS_NULL_ARGUMENT;
+    }
+    *pResult = (de_result_t){ .best = { .coordinates = NULL, .num_dimensions = 0U }, .history = NULL };
+    de_status_t status = de_validate_islands(pOptimizationTarget, pConfig, pIslandConfig);
+    if (status != DE_STATUS_OK) {
+        return status;
+    }
+
+    uint32_t islandCount = pIslandConfig->island_count;
+    pDeState_t* islands = malloc(islandCount * sizeof(pDeState_t));
+    de_island_epoch_t* epochs = malloc(islandCount * sizeof(de_island_epoch_t));
+    de_worker_pool_t* pPool = islandCount > 1U ? de_worker_pool_create(islandCount) : NULL; // island i > 0 keeps its thread for the whole run
+    de_optimization_target_t target = *pOptimizationTarget;
+    target.thread_user_data = NULL; // every island evaluates on its own thread
+    de_config_t config = *pConfig;
+    if (islandCount > 1U) {
+        config.progress_callback = NULL;
+    }
+    for (uint32_t i = 0; i < islandCount; i++) {
+        config.seed = i == 0U || pConfig->seed == 0U ? pConfig->seed : de_seed_for_stream(pConfig->seed, i);
+        void* pIslandUserData = pOptimizationTarget->thread_user_data != NULL ? pOptimizationTarget->thread_user_data[i] : pUserData;
+        de_init(&target, &config, pIslandUserData, &islands[i]); // validated above
+        epochs[i] = (de_island_epoch_t){ .pState = islands[i], .generationCount = pIslandConfig->migration_interval };
+    }
+
+    int32_t stoppedIsland = -1;
+    while (stoppedIsland < 0) {
+        de_worker_pool_run(pPool, de_run_island_epoch, epochs, sizeof(de_island_epoch_t), islandCount);
+        for (uint32_t i = 0; i < islandCount && stoppedIsland < 0; i++) {
+            if (islands[i]->is_stopped) {
+                stoppedIsland = (int32_t)i;
+            }
+        }
+        if (stoppedIsland < 0 && islandCount > 1U && pIslandConfig->migration_count > 0U) {
+            de_migrate(islands, pIslandConfig);
+        }
+    }
+
+    uint32_t bestIsland = 0U;
+    uint64_t evaluationCount = 0U;
+    uint64_t strategyTrials[DE_MUTATION_STRATEGY_COUNT] = { 0U };
+    uint64_t strategySuccesses[DE_MUTATION_STRATEGY_COUNT] = { 0U };
+    for (uint32_t i = 0; i < islandCount; i++) {
+        pDeWorkspace_t pWorkspace = &islands[i]->workspace;
+        for (uint32_t k = 0; k < DE_MUTATION_STRATEGY_COUNT; k++) {
+            strategyTrials[k] += pWorkspace->strategy_trials[k];
+            strategySuccesses[k] += pWorkspace->strategy_successes[k];
+        }
+        pDeWorkspace_t pBestWorkspace = &islands[bestIsland]->workspace;
+        if (de_is_better(pBestWorkspace, &islands[bestIsland]->config, pWorkspace->p_main_best_val, pWorkspace->p_main_best_violation, pBestWorkspace->p_main_best_val, pBestWorkspace->p_main_best_violation)) {
+            bestIsland = i;
+        }
+        evaluationCount += pWorkspace->evaluation_count;
+    }
+    de_stop_reason_t stopReason = islands[stoppedIsland]->stop_reason;
+    uint64_t generationCount = islands[stoppedIsland]->iter_count;
+    for (uint32_t i = 0; i < islandCount; i++) {
+        de_finish(islands[i], i == bestIsland ? pResult : NULL);
+    }
+    pResult->generations = generationCount;
+    pResult->evaluations = evaluationCount;
+    pResult->stop_reason = stopReason;
+    for (uint32_t k = 0; k < DE_MUTATION_STRATEGY_COUNT; k++) {
+        pResult->strategy_trials[k] = strategyTrials[k];
+        pResult->strategy_successes[k] = strategySuccesses[k];
+    }
+
+    de_worker_pool_free(pPool);
+    free(epochs);
+    free(islands);
+    return DE_STATUS_OK;
+}
+
+de_status_t de_niching_minimum(pDeOptimizationTarget_t pOptimizationTarget, pDeConfig_t pConfig, double radius, void* pUserData, pDeOptima_t pOptima) {
+    if (pOptima == NULL) {
+        return DE_STATUS_NULL_ARGUMENT;
+    }
+    *pOptima = (de_optima_t){ .coordinates = NULL, .fitness = NULL, .violation = NULL };
+    if (!(radius >= 0.0 && isfinite(radius))) {
+        return DE_STATUS_INVALID_NICHE_RADIUS;
+    }
+    de_config_t config;
+    if (pConfig != NULL) {
+        config = *pConfig;
+        config.selection = DE_SELECTION_CROWDING;
+    }
+    pDeState_t pState;
+    de_status_t status = de_init(pOptimizationTarget, pConfig != NULL ? &config : NULL, pUserData, &pState);
+    if (status != DE_STATUS_OK) {
+        return status;
+    }
+    while (!de_step(pState)) {
+    }
+    de_distinct_optima(pState, radius, pOptima); // the radius is valid
+    de_finish(pState, NULL);
+
+    return DE_STATUS_OK;
+}
@@ -15,18 +15,51 @@ void de_vector_free_coordinates (pVector_t pVec) { free(pVec->coordinates); }
 
 typedef double (*RdR_Function)(vector_t, void*);
 
+/**
+ * Batched objective: evaluate `count` row-major vectors of `numDimensions` coordinates from `pPopulation` into `pFitness`
+*/
+typedef void (*RdR_BatchFunction)(const double* pPopulation, uint32_t count, uint32_t numDimensions, double* pFitness, void*);
+
+/**
+ * Constraints: write the `num_inequality_constraints` values g_k (feasible if g_k <= 0) followed by the `num_equality_constraints` values h_k (feasible if h_k = 0) to `pValues`
+ * @note Called with the same user data (and from the same threads) as the objective
+*/
+typedef void (*RdR_ConstraintFunction)(vector_t, double* pValues, void*);
+
+/**
+ * Kind of a coordinate, integer and categorical coordinates only take the integer values within their bounds
+*/
+typedef enum {
+    DE_VARIABLE_CONTINUOUS,
+    DE_VARIABLE_INTEGER, // ordered, mutants are rounded to the nearest integer
+    DE_VARIABLE_CATEGORICAL // unordered index, a mutant inherits the category of its base vector and switches to a random one with probability F if the difference vectors disagree
+} de_variable_type_t;
+
 typedef struct {
-    RdR_Function f;
+    RdR_Function f; // may be NULL if `f_batch` is given
+    RdR_BatchFunction f_batch; // optional, evaluates whole populations instead of `f`
     uint32_t num_dimensions;
-    double left_bound;
-    double right_bound;
+    const double* left_bounds; // num_dimensions lower bounds, one per coordinate
+    const double* right_bounds; // num_dimensions upper bounds, one per coordinate
+    const double* initial_population; // optional warm start, initial_population_size rows of num_dimensions coordinates (row-major)
+    uint32_t initial_population_size; // at most population_size, the remaining members are drawn uniformly
+    uint8_t is_thread_safe; // 1 if the objective may run concurrently on the shared user data (needed for thread_count > 1 without thread_user_data)
+    void* const* thread_user_data; // optional, thread_count user data pointers, thread t evaluates with thread_user_data[t] instead of the shared one
+    RdR_ConstraintFunction g; // optional, NULL for an unconstrained target
+    uint32_t num_inequality_constraints;
+    uint32_t num_equality_constraints;
+    const de_variable_type_t* variable_types; // optional, num_dimensions entries, NULL treats every coordinate as continuous
 } de_optimization_target_t;
 
 typedef de_optimization_target_t* pDeOptimizationTarget_t;
 
 typedef enum {
     STOP_AFTER_ITERS,
-    STOP_WHEN_SATISFIED
+    STOP_WHEN_SATISFIED,
+    STOP_AFTER_EVALUATIONS, // checked after every generation, so the budget may be overshot by less than one generation
+    STOP_ON_STAGNATION, // no improvement of the best fitness for `stagnation_iters` generations
+    STOP_ON_CONVERGENCE, // max - min fitness of the population at most `fitness_spread`
+    STOP_AFTER_SECONDS // wall-clock time limit
 } de_stop_type_t;
 
 typedef struct {
@@ -34,22 +67,405 @@ typedef struct {
     union {
         uint64_t iters;
         double accuracy; 
+        uint64_t evaluations;
+        uint64_t stagnation_iters;
+        double fitness_spread;
+        double seconds;
     } limitation;
 } de_stop_condition_t;
 
+#define DE_MAX_EXTRA_STOP_CONDITIONS 4
+
+typedef enum {
+    DE_STOP_WHEN_ANY, // stop as soon as one of the conditions holds
+    DE_STOP_WHEN_ALL // stop once all of the conditions hold at the same time
+} de_stop_combination_t;
+
+typedef enum {
+    DE_MUTATION_RAND_BEST_1, // v = lambda * x_best + (1 - lambda) * x_i + F * (x_r1 - x_r2)
+    DE_MUTATION_RAND_1, // v = x_r1 + F * (x_r2 - x_r3)
+    DE_MUTATION_BEST_1, // v = x_best + F * (x_r1 - x_r2)
+    DE_MUTATION_CURRENT_TO_BEST_1, // v = x_i + F * (x_best - x_i) + F * (x_r1 - x_r2)
+    DE_MUTATION_RAND_2, // v = x_r1 + F * (x_r2 - x_r3) + F * (x_r4 - x_r5)
+    DE_MUTATION_BEST_2, // v = x_best + F * (x_r1 - x_r2) + F * (x_r3 - x_r4)
+    DE_MUTATION_CURRENT_TO_PBEST_1 // v = x_i + F * (x_pbest - x_i) + F * (x_r1 - x_r2), x_pbest drawn from the best p * NP members
+} de_mutation_strategy_t;
+
+#define DE_MUTATION_STRATEGY_COUNT 7
+
+/**
+ * Strategy pool bit of a mutation strategy for `de_config_t.strategy_pool`
+*/
+#define DE_STRATEGY_BIT(strategy) (1U << (strategy))
+
+/**
+ * Pool of the ensembles if `de_config_t.strategy_pool` is 0: DE/rand/1, DE/rand/2, DE/best/2 and DE/current-to-best/1
+*/
+#define DE_DEFAULT_STRATEGY_POOL (DE_STRATEGY_BIT(DE_MUTATION_RAND_1) | DE_STRATEGY_BIT(DE_MUTATION_RAND_2) | DE_STRATEGY_BIT(DE_MUTATION_BEST_2) | DE_STRATEGY_BIT(DE_MUTATION_CURRENT_TO_BEST_1))
+
+typedef enum {
+    DE_CROSSOVER_BINOMIAL, // every coordinate independently taken from the mutant with probability CR
+    DE_CROSSOVER_EXPONENTIAL // a contiguous (cyclic) run of coordinates taken from the mutant, extended while U(0,1) < CR
+} de_crossover_type_t;
+
+/**
+ * What happens to a mutant coordinate that left its [left_bounds[j]; right_bounds[j]] range
+*/
+typedef enum {
+    DE_BOUNDARY_CLAMP, // set to the violated bound
+    DE_BOUNDARY_REFLECT, // mirror back into the range at the violated bound (repeatedly, for far-off coordinates)
+    DE_BOUNDARY_REINITIALIZE, // draw uniformly from the range
+    DE_BOUNDARY_MIDPOINT // halfway between the parent coordinate and the violated bound
+} de_boundary_handling_t;
+
+#define DE_SOBOL_MAX_DIMENSIONS 30
+
+/**
+ * How the members not given by `de_optimization_target_t.initial_population` are placed
+ * @note The quasi-random sequences are randomly shifted (mod 1) per dimension, so different seeds still give different populations
+*/
+typedef enum {
+    DE_INIT_UNIFORM, // independent uniform draws
+    DE_INIT_LATIN_HYPERCUBE, // every dimension split into NP equal strata, each stratum hit exactly once
+    DE_INIT_HALTON, // Halton sequence (one prime base per dimension)
+    DE_INIT_SOBOL, // Sobol sequence (Joe-Kuo direction numbers), at most DE_SOBOL_MAX_DIMENSIONS dimensions
+    DE_INIT_OPPOSITION // uniform draws plus their opposites l + r - x, the fittest NP of both are kept (costs NP extra evaluations)
+} de_initialization_t;
+
+/**
+ * Online adaptation of F and CR (the ensembles: also of the mutation strategy) from the trial vectors that replaced their parent
+ * @note With JADE and SHADE every trial i draws its own F_i ~ Cauchy(mu_F, 0.1) (truncated to (0; 1]) and CR_i ~ N(mu_CR, 0.1) (clipped to [0; 1]),
+ *       the means (jDE: the per-member values) start at `amplification_factor` and `crossover_probability`
+ * @note The ensembles ignore `mutation_strategy` and draw strategy k from `strategy_pool` with probability p_k ~ S_k = s_k / (s_k + f_k) + 0.01,
+ *       s_k and f_k counting the successful and failed trials of k over the last `learning_period` generations (uniform during the first ones)
+*/
+typedef enum {
+    DE_ADAPTATION_NONE, // F and CR fixed for the whole run
+    DE_ADAPTATION_JADE, // mu_CR <- (1 - c) mu_CR + c mean(S_CR), mu_F <- (1 - c) mu_F + c lehmer_mean(S_F) with c = `adaptation_rate`
+    DE_ADAPTATION_SHADE, // `history_size` memory slots of (mu_F, mu_CR) updated round-robin with fitness-improvement weighted means
+    DE_ADAPTATION_JDE, // every member carries its own F and CR, a trial regenerates them with probability `jde_tau1` (F ~ U[0.1; 1)) and `jde_tau2` (CR ~ U[0; 1))
+    DE_ADAPTATION_SADE, // every trial draws a strategy k, F ~ N(0.5, 0.3) (truncated to (0; 2]) and CR ~ N(CRm_k, 0.1), CRm_k the mean successful CR of k over the learning period
+    DE_ADAPTATION_EPSDE // every member carries a strategy and an (F, CR) pair from {0.4, 0.5, ..., 0.9} x {0.1, 0.2, ..., 0.9}, kept while its trials succeed and redrawn after a failure
+} de_adaptation_t;
+
+/**
+ * Which member of the population a trial vector competes with
+*/
+typedef enum {
+    DE_SELECTION_PARENT, // the member it was created from
+    DE_SELECTION_CROWDING // the member nearest to it (Euclidean distance), so the population can settle on several optima at once
+} de_selection_t;
+
+/**
+ * How selection compares two vectors by fitness f and total constraint violation v = sum max(0, g_k) + sum max(0, |h_k| - `equality_tolerance`)
+ * @note Without constraints v is always 0 and every method compares by fitness alone
+*/
+typedef enum {
+    DE_CONSTRAINTS_FEASIBILITY_RULES, // Deb's rules: feasible beats infeasible, two feasible by f, two infeasible by v
+    DE_CONSTRAINTS_PENALTY, // by f + `penalty_factor` * v
+    DE_CONSTRAINTS_EPSILON // feasibility rules with every v <= epsilon counting as feasible, epsilon falls from v_0 as v_0 (1 - t / T_c)^cp to 0 at generation T_c = `epsilon_generations`
+} de_constraint_handling_t;
+
+/**
+ * Read-only view of the population handed to a `de_progress_callback_t`, only valid during the call
+*/
 typedef struct {
-    uint32_t population_size;
+    const vector_t* members;
+    const double* fitness;
+    uint32_t size;
+} de_population_view_t;
+
+/**
+ * Called after every generation
+ * @returns Non-zero to stop the run (with DE_STOP_REASON_CANCELLED unless a stop condition holds as well)
+*/
+typedef int (*de_progress_callback_t)(uint64_t generation, double bestFitness, const de_population_view_t* pPopulation, void* pUserData);
+
+typedef struct {
+    uint32_t population_size; // initial size with linear population size reduction
+    uint32_t final_population_size; // L-SHADE style reduction: the worst members are dropped so the size falls linearly to this, 0 keeps the size constant
+    uint64_t reduction_evaluations; // evaluation budget over which the size falls to `final_population_size`
     double crossover_probability; // CR (pc) [0; 1]
     double amplification_factor; // F [0; 2]
     double lambda; // DE/rand_best/1/bin specific x_best weight [0; 1]
+    de_mutation_strategy_t mutation_strategy;
+    double p_best_fraction; // DE/current-to-pbest/1 specific p (0; 1]
+    de_crossover_type_t crossover_type;
+    de_boundary_handling_t boundary_handling;
+    de_initialization_t initialization;
+    de_adaptation_t adaptation;
+    double adaptation_rate; // JADE specific c (0; 1]
+    uint32_t history_size; // SHADE specific number of memory slots, at least 1
+    double jde_tau1; // jDE specific probability of regenerating F [0; 1]
+    double jde_tau2; // jDE specific probability of regenerating CR [0; 1]
+    double archive_rate; // capacity of the external archive of replaced parents in multiples of population_size, 0 disables it (only filled while DE/current-to-pbest/1 can be selected)
+    uint32_t strategy_pool; // SaDE/EPSDE specific set of `DE_STRATEGY_BIT`s to choose from, 0 means DE_DEFAULT_STRATEGY_POOL
+    uint32_t learning_period; // SaDE/EPSDE specific number of generations the success rates are counted over, at least 1
+    de_selection_t selection;
+    de_constraint_handling_t constraint_handling;
+    double penalty_factor; // DE_CONSTRAINTS_PENALTY specific weight of the violation (0; inf)
+    double equality_tolerance; // |h_k| up to this counts as satisfied [0; inf)
+    uint64_t epsilon_generations; // DE_CONSTRAINTS_EPSILON specific T_c, at least 1 (v_0 is the violation of the member at 20% of the initial population sorted by violation)
+    double epsilon_exponent; // DE_CONSTRAINTS_EPSILON specific cp (0; inf)
     de_stop_condition_t stop_condition;
+    de_stop_condition_t extra_stop_conditions[DE_MAX_EXTRA_STOP_CONDITIONS]; // combined with `stop_condition`
+    uint32_t extra_stop_condition_count;
+    de_stop_combination_t stop_combination;
+    uint64_t max_generations; // hard cap on top of the stop conditions, 0 means no cap (only valid if the conditions are bounded by themselves)
+    uint64_t seed; // Mersenne Twister seed, 0 means "pick a fresh non-reproducible seed for every run"
+    uint8_t record_history; // 1 to fill `de_result_t.history` with per-generation statistics
+    uint32_t thread_count; // threads evaluating the population in contiguous chunks, started once per run, 0 or 1 evaluates on the calling thread
+    de_progress_callback_t progress_callback; // optional, NULL for none
+    void* progress_user_data; // passed to `progress_callback`
 } de_config_t;
 
 typedef de_config_t* pDeConfig_t;
 
 /**
- * Differential evolution with DE/rand_best/1/bin mutation
- * @attention The coordinates buffer of the returned vector need to be freed, please use `vector_free_coordinates(pVector_t)`
+ * Which condition stopped the run, with DE_STOP_WHEN_ALL it is the reason of `de_config_t.stop_condition`
+*/
+typedef enum {
+    DE_STOP_REASON_ITERS_EXHAUSTED, // STOP_AFTER_ITERS limit or `max_generations` reached
+    DE_STOP_REASON_ACCURACY_REACHED,
+    DE_STOP_REASON_EVALUATIONS_EXHAUSTED,
+    DE_STOP_REASON_STAGNATED,
+    DE_STOP_REASON_CONVERGED,
+    DE_STOP_REASON_TIME_LIMIT_REACHED,
+    DE_STOP_REASON_CANCELLED // stopped by the progress callback, or `de_finish` before the stop conditions held
+} de_stop_reason_t;
+
+typedef enum {
+    DE_STATUS_OK,
+    DE_STATUS_NULL_ARGUMENT, // NULL target, config, bounds, result or state, neither `f` nor `f_batch`, or constraints without `g`
+    DE_STATUS_INVALID_DIMENSIONS, // num_dimensions (and num_objectives) must be at least 1
+    DE_STATUS_INVALID_BOUNDS, // bounds must be finite with left_bounds[j] < right_bounds[j] for every j
+    DE_STATUS_INVALID_POPULATION_SIZE, // population_size (and a non-zero final_population_size <= population_size) must exceed `de_mutation_donor_count` of the strategy (of every pooled one), reduction needs reduction_evaluations
+    DE_STATUS_INVALID_CROSSOVER_PROBABILITY, // CR outside [0; 1]
+    DE_STATUS_INVALID_AMPLIFICATION_FACTOR, // F outside [0; 2]
+    DE_STATUS_INVALID_LAMBDA, // lambda outside [0; 1]
+    DE_STATUS_INVALID_P_BEST_FRACTION, // p outside (0; 1] for DE/current-to-pbest/1 (also as part of the strategy pool)
+    DE_STATUS_INVALID_STRATEGY, // unknown mutation strategy, crossover type, boundary handling or selection
+    DE_STATUS_INVALID_STOP_CONDITION, // unknown type, invalid limitation, conditions that might never hold without `max_generations`, or a fitness-based condition in a multi-objective run
+    DE_STATUS_INVALID_INITIAL_POPULATION, // more rows than population_size, NULL rows, a coordinate outside its bounds or a non-integer discrete coordinate
+    DE_STATUS_INVALID_INITIALIZATION, // unknown initialization, DE_INIT_SOBOL with more than DE_SOBOL_MAX_DIMENSIONS dimensions, or DE_INIT_OPPOSITION in a multi-objective run
+    DE_STATUS_INVALID_ADAPTATION, // unknown adaptation, adaptation_rate outside (0; 1], zero history_size with SHADE, jDE tau outside [0; 1], archive_rate outside [0; 10],
+                                  // or for the ensembles an unknown strategy bit in strategy_pool or zero learning_period
+    DE_STATUS_INVALID_CONFIG_CHANGE, // `de_set_config` changing population_size, final_population_size, adaptation, history_size, archive_rate, or learning_period or strategy_pool under SaDE/EPSDE
+    DE_STATUS_INVALID_THREAD_COUNT, // thread_count > 1 with an objective that is neither thread-safe nor has per-thread user data, or with more than one island
+    DE_STATUS_INVALID_CONSTRAINT_HANDLING, // unknown method, negative equality_tolerance, penalty_factor outside (0; inf) or epsilon parameters not positive
+    DE_STATUS_INVALID_VARIABLE_TYPES, // unknown variable type, or an integer or categorical coordinate without an integer within its bounds
+    DE_STATUS_INVALID_ISLAND_CONFIG, // no islands, zero migration_interval, unknown topology, or as many immigrants as an island has members
+    DE_STATUS_INVALID_NICHE_RADIUS // radius not finite or negative
+} de_status_t;
+
+typedef struct {
+    double best_fitness;
+    double mean_fitness;
+} de_generation_stats_t;
+
+typedef struct {
+    vector_t best;
+    double best_fitness;
+    uint64_t generations;
+    uint64_t evaluations; // number of calls to the objective function
+    de_stop_reason_t stop_reason;
+    de_generation_stats_t* history; // one entry per generation, NULL unless `de_config_t.record_history` was set
+    uint64_t history_length;
+    double best_violation; // total constraint violation of `best`, 0 without constraints
+    uint8_t is_feasible; // 1 if `best` satisfies all constraints
+    uint64_t strategy_trials[DE_MUTATION_STRATEGY_COUNT]; // trial vectors created with each `de_mutation_strategy_t`
+    uint64_t strategy_successes[DE_MUTATION_STRATEGY_COUNT]; // of those, the ones that replaced their parent
+} de_result_t;
+
+typedef de_result_t* pDeResult_t;
+
+/**
+ * Derive a seed for an independent random stream from a base seed and a stream index (e.g. thread number or try number)
+ * @returns A non-zero seed, suitable for `de_config_t.seed`
+*/
+uint64_t de_seed_for_stream(uint64_t baseSeed, uint64_t streamIndex);
+
+/**
+ * @returns The number of random population members (besides x_i) the mutation strategy draws, the population must be larger than that
+*/
+uint32_t de_mutation_donor_count(de_mutation_strategy_t strategy);
+
+/**
+ * Check the target and config without running the optimization
+ * @returns DE_STATUS_OK or the first problem found
+*/
+de_status_t de_validate(pDeOptimizationTarget_t pOptimizationTarget, pDeConfig_t pConfig);
+
+/**
+ * Free the buffers owned by a result filled by `de_minimum`
+*/
+void de_result_free(pDeResult_t pResult);
+
+/**
+ * Differential evolution with selectable mutation strategy (DE/rand_best/1/bin by default)
+ * @note With a non-zero `pConfig->seed` the result is reproducible bit-for-bit for the same target, config and objective
+ * @returns DE_STATUS_OK, or the `de_validate` error in which case nothing is run and `*pResult` holds no buffers
+ * @attention The buffers of `*pResult` need to be freed, please use `de_result_free(pDeResult_t)`
+*/
+de_status_t de_minimum(de_optimization_target_t* pOptimizationTarget, de_config_t* pConfig, void* pUserData, pDeResult_t pResult);
+
+/**
+ * State of a stepwise run, see `de_init`
+*/
+typedef struct de_state_s de_state_t;
+
+typedef de_state_t* pDeState_t;
+
+/**
+ * Start a stepwise run: validate, create and evaluate the initial population
+ * @note `pOptimizationTarget->f` (or `f_batch`), the bounds and `thread_user_data` must stay valid until `de_finish`, the target and config structs themselves are copied
+ * @returns DE_STATUS_OK, or the `de_validate` error in which case `*ppState` is NULL
+ * @attention The state needs to be released with `de_finish(pDeState_t, pDeResult_t)`
+*/
+de_status_t de_init(pDeOptimizationTarget_t pOptimizationTarget, pDeConfig_t pConfig, void* pUserData, de_state_t** ppState);
+
+/**
+ * Run one generation
+ * @returns 1 once the stop conditions of the config hold, 0 otherwise (stepping on is possible either way)
+*/
+uint8_t de_step(pDeState_t pState);
+
+/**
+ * @returns The best fitness so far, its coordinates are copied to `pCoordinates` (num_dimensions entries) unless it is NULL
+*/
+double de_best(pDeState_t pState, double* pCoordinates);
+
+/**
+ * @returns The total constraint violation of the best member, 0 if it is feasible
+*/
+double de_best_violation(pDeState_t pState);
+
+/**
+ * @returns The current population size, smaller than `population_size` with linear population size reduction
+*/
+uint32_t de_population_size(pDeState_t pState);
+
+/**
+ * Copy the current population, row-major into `pCoordinates` (`de_population_size` rows of num_dimensions) and the fitness into `pFitness`, either may be NULL
+*/
+void de_population(pDeState_t pState, double* pCoordinates, double* pFitness);
+
+uint64_t de_generations(pDeState_t pState);
+
+uint64_t de_evaluations(pDeState_t pState);
+
+/**
+ * Replace the config of a running optimization, it is validated like in `de_init`
+ * @returns DE_STATUS_OK, a `de_validate` error or DE_STATUS_INVALID_CONFIG_CHANGE, in both latter cases the old config stays in place
 */
-vector_t de_minimum(de_optimization_target_t* pOptimizationTarget, de_config_t* pConfig, void* pUserData);
+de_status_t de_set_config(pDeState_t pState, pDeConfig_t pConfig);
 
+typedef struct {
+    uint32_t size; // number of distinct optima
+    uint32_t num_dimensions;
+    double* coordinates; // size rows of num_dimensions (row-major), best first
+    double* fitness; // size values, in the same order
+    double* violation; // size total constraint violations, in the same order
+    uint64_t generations;
+    uint64_t evaluations; // number of calls to the objective function
+    de_stop_reason_t stop_reason; // DE_STOP_REASON_CANCELLED when taken from a running state
+} de_optima_t;
+
+typedef de_optima_t* pDeOptima_t;
+
+/**
+ * Free the buffers owned by optima filled by `de_distinct_optima` or `de_niching_minimum`
+*/
+void de_optima_free(pDeOptima_t pOptima);
+
+/**
+ * Collect the distinct optima of the current population: going from the best member to the worst, a member is kept unless it lies within `radius` (Euclidean distance) of one kept before
+ * @returns DE_STATUS_OK, or DE_STATUS_INVALID_NICHE_RADIUS in which case `*pOptima` holds no buffers
+ * @attention The buffers of `*pOptima` need to be freed, please use `de_optima_free(pDeOptima_t)`
+*/
+de_status_t de_distinct_optima(pDeState_t pState, double radius, pDeOptima_t pOptima);
+
+/**
+ * End a stepwise run and release its state, filling `*pResult` unless it is NULL
+ * @note Unless the last `de_step` returned 1 the stop reason is DE_STOP_REASON_CANCELLED
+ * @attention The buffers of `*pResult` need to be freed, please use `de_result_free(pDeResult_t)`
+*/
+void de_finish(pDeState_t pState, pDeResult_t pResult);
+
+/**
+ * Multi-objective function: write the `num_objectives` values of the vector to `pObjectives`, all of them are minimized
+*/
+typedef void (*RdR_MultiFunction)(vector_t, double* pObjectives, void*);
+
+typedef struct {
+    RdR_MultiFunction f;
+    uint32_t num_objectives;
+    uint32_t num_dimensions;
+    const double* left_bounds; // num_dimensions lower bounds, one per coordinate
+    const double* right_bounds; // num_dimensions upper bounds, one per coordinate
+    const de_variable_type_t* variable_types; // optional, num_dimensions entries, NULL treats every coordinate as continuous
+} de_multi_optimization_target_t;
+
+typedef de_multi_optimization_target_t* pDeMultiOptimizationTarget_t;
+
+typedef struct {
+    uint32_t size; // number of non-dominated points
+    uint32_t num_dimensions;
+    uint32_t num_objectives;
+    double* coordinates; // size rows of num_dimensions (row-major), sorted by the first objective
+    double* objectives; // size rows of num_objectives, in the same order
+    uint64_t generations;
+    uint64_t evaluations; // number of calls to the objective function
+    de_stop_reason_t stop_reason;
+} de_pareto_front_t;
+
+typedef de_pareto_front_t* pDeParetoFront_t;
+
+/**
+ * Free the buffers owned by a front filled by `de_pareto_minimum`
+*/
+void de_pareto_front_free(pDeParetoFront_t pFront);
+
+/**
+ * Multi-objective differential evolution (GDE3): a DE/rand/1 trial replaces the parent it weakly dominates, is dropped if the parent weakly dominates it
+ * and joins the population otherwise, which is then cut back to population_size by non-dominated sorting and crowding distance
+ * @note Of the config only population_size, crossover_probability, amplification_factor, crossover_type, boundary_handling, initialization (except DE_INIT_OPPOSITION),
+ *       the stop conditions (STOP_AFTER_ITERS, STOP_AFTER_EVALUATIONS and STOP_AFTER_SECONDS), max_generations and seed are used
+ * @returns DE_STATUS_OK, or the validation error in which case nothing is run and `*pFront` holds no buffers
+ * @attention The buffers of `*pFront` need to be freed, please use `de_pareto_front_free(pDeParetoFront_t)`
+*/
+de_status_t de_pareto_minimum(pDeMultiOptimizationTarget_t pOptimizationTarget, pDeConfig_t pConfig, void* pUserData, pDeParetoFront_t pFront);
+
+typedef enum {
+    DE_MIGRATION_RING, // island i sends to island i + 1 (mod island_count)
+    DE_MIGRATION_FULLY_CONNECTED // every island sends to every other island
+} de_migration_topology_t;
+
+typedef struct {
+    uint32_t island_count; // populations evolving in their own threads, each of `de_config_t.population_size` members
+    uint32_t migration_interval; // generations between migrations, at least 1
+    uint32_t migration_count; // best members an island sends along each edge of the topology, they replace the worst members of the receiver, 0 isolates the islands
+    de_migration_topology_t topology;
+} de_island_config_t;
+
+typedef de_island_config_t* pDeIslandConfig_t;
+
+/**
+ * Island model: `island_count` populations evolve in parallel threads and exchange their best members every `migration_interval` generations
+ * @note Island 0 runs with `pConfig->seed`, island i with `de_seed_for_stream(seed, i)`, so a single island gives the result of `de_minimum`
+ * @note Every island checks the stop conditions on its own, the run ends after the migration interval in which the first island stopped (with its stop reason),
+ *       the result holds the best island's member and history, the generations of one island and the evaluations and strategy statistics of all of them
+ * @note With more than one island the objective has to be thread-safe, or `thread_user_data` has to hold island_count user data pointers (island i uses entry i),
+ *       thread_count must be 0 or 1 and the progress callback is not called
+ * @returns DE_STATUS_OK, or the validation error in which case nothing is run and `*pResult` holds no buffers
+ * @attention The buffers of `*pResult` need to be freed, please use `de_result_free(pDeResult_t)`
+*/
+de_status_t de_island_minimum(pDeOptimizationTarget_t pOptimizationTarget, pDeConfig_t pConfig, pDeIslandConfig_t pIslandConfig, void* pUserData, pDeResult_t pResult);
+
+/**
+ * Niching differential evolution: run with DE_SELECTION_CROWDING (whatever `pConfig->selection` says) and return the distinct optima of the final population, see `de_distinct_optima`
+ * @note Strategies guided by the best member pull the niches together, DE/rand/1 with a large population is the usual choice
+ * @returns DE_STATUS_OK, or the validation error in which case nothing is run and `*pOptima` holds no buffers
+ * @attention The buffers of `*pOptima` need to be freed, please use `de_optima_free(pDeOptima_t)`
+*/
+de_status_t de_niching_minimum(pDeOptimizationTarget_t pOptimizationTarget, pDeConfig_t pConfig, double radius, void* pUserData, pDeOptima_t pOptima);
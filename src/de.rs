@@ -1,4 +1,11 @@
-use libc::{c_double, c_void};
+use std::any::Any;
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::ops::ControlFlow;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Mutex;
+
+use libc::{c_double, c_int, c_void};
 use crate::vector::VectorN;
 
 #[repr(C)]
@@ -30,14 +37,22 @@ impl Vector {
 #[derive(Clone, Copy)]
 pub enum DeStopType {
     StopAfterIters,
-    StopWhenSatisfied
+    StopWhenSatisfied,
+    StopAfterEvaluations,
+    StopOnStagnation,
+    StopOnConvergence,
+    StopAfterSeconds
 }
 
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub union DeLimitation {
     pub iters: u64,
-    pub accuracy: c_double
+    pub accuracy: c_double,
+    pub evaluations: u64,
+    pub stagnation_iters: u64,
+    pub fitness_spread: c_double,
+    pub seconds: c_double
 }
 
 #[repr(C)]
@@ -47,30 +62,1738 @@ pub struct DeStopCondition {
     pub union: DeLimitation
 }
 
+impl DeStopCondition {
+    pub fn after_iters(iters: u64) -> Self {
+        return Self { stype: DeStopType::StopAfterIters, union: DeLimitation { iters } };
+    }
+    pub fn when_satisfied(accuracy: f64) -> Self {
+        return Self { stype: DeStopType::StopWhenSatisfied, union: DeLimitation { accuracy } };
+    }
+    pub fn after_evaluations(evaluations: u64) -> Self {
+        return Self { stype: DeStopType::StopAfterEvaluations, union: DeLimitation { evaluations } };
+    }
+    /// No improvement of the best fitness for `stagnation_iters` generations
+    pub fn on_stagnation(stagnation_iters: u64) -> Self {
+        return Self { stype: DeStopType::StopOnStagnation, union: DeLimitation { stagnation_iters } };
+    }
+    /// Fitness spread (max - min) of the population at most `fitness_spread`
+    pub fn on_convergence(fitness_spread: f64) -> Self {
+        return Self { stype: DeStopType::StopOnConvergence, union: DeLimitation { fitness_spread } };
+    }
+    pub fn after_seconds(seconds: f64) -> Self {
+        return Self { stype: DeStopType::StopAfterSeconds, union: DeLimitation { seconds } };
+    }
+}
+
+pub const DE_MAX_EXTRA_STOP_CONDITIONS: usize = 4;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeStopCombination {
+    Any,
+    All
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DeMutationStrategy {
+    #[value(name = "rand-best-1")]
+    RandBest1,
+    #[value(name = "rand-1")]
+    Rand1,
+    #[value(name = "best-1")]
+    Best1,
+    #[value(name = "current-to-best-1")]
+    CurrentToBest1,
+    #[value(name = "rand-2")]
+    Rand2,
+    #[value(name = "best-2")]
+    Best2,
+    #[value(name = "current-to-pbest-1")]
+    CurrentToPBest1
+}
+
+pub const DE_MUTATION_STRATEGY_COUNT: usize = 7;
+
+impl DeMutationStrategy {
+    pub const ALL: [DeMutationStrategy; DE_MUTATION_STRATEGY_COUNT] = [Self::RandBest1, Self::Rand1, Self::Best1, Self::CurrentToBest1, Self::Rand2, Self::Best2, Self::CurrentToPBest1];
+
+    /// The bit of the strategy in `DeConfig::strategy_pool`
+    pub fn pool_bit(self) -> u32 {
+        return 1 << self as u32;
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DeCrossoverType {
+    #[value(name = "bin")]
+    Binomial,
+    #[value(name = "exp")]
+    Exponential
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DeBoundaryHandling {
+    #[value(name = "clamp")]
+    Clamp,
+    #[value(name = "reflect")]
+    Reflect,
+    #[value(name = "reinit")]
+    Reinitialize,
+    #[value(name = "midpoint")]
+    Midpoint
+}
+
+pub const DE_SOBOL_MAX_DIMENSIONS: usize = 30;
+
+/// Placement of the initial members that are not given as warm start
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DeInitialization {
+    #[value(name = "uniform")]
+    Uniform,
+    #[value(name = "lhs")]
+    LatinHypercube,
+    #[value(name = "halton")]
+    Halton,
+    #[value(name = "sobol")]
+    Sobol, // at most DE_SOBOL_MAX_DIMENSIONS dimensions
+    #[value(name = "opposition")]
+    Opposition // costs population_size extra evaluations
+}
+
+/// Online adaptation of F and CR, `amplification_factor` and `crossover_probability` become the initial means,
+/// the ensembles also pick the mutation strategy of every trial from `strategy_pool` by its recent success rate
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DeAdaptation {
+    #[value(name = "none")]
+    None,
+    #[value(name = "jade")]
+    Jade,
+    #[value(name = "shade")]
+    Shade,
+    #[value(name = "jde")]
+    Jde,
+    #[value(name = "sade")]
+    Sade, // F ~ N(0.5, 0.3), CR ~ N(mean successful CR of the strategy, 0.1)
+    #[value(name = "epsde")]
+    Epsde // every member keeps its strategy and (F, CR) pair from fixed pools while its trials succeed
+}
+
+/// Kind of a coordinate, integer and categorical ones only take the integer values within their bounds
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DeVariableType {
+    #[value(name = "continuous")]
+    Continuous,
+    #[value(name = "integer")]
+    Integer, // ordered, mutants are rounded
+    #[value(name = "categorical")]
+    Categorical // unordered index, mutants inherit or randomly switch categories instead of blending them
+}
+
+/// Which member a trial vector competes with
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DeSelection {
+    #[value(name = "parent")]
+    Parent,
+    #[value(name = "crowding")]
+    Crowding // the nearest member, keeps the population spread over several optima
+}
+
+/// How selection weighs the total constraint violation v against the fitness f, without constraints all of them compare by f
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DeConstraintHandling {
+    #[value(name = "feasibility")]
+    FeasibilityRules, // feasible beats infeasible, two feasible by f, two infeasible by v
+    #[value(name = "penalty")]
+    Penalty, // by f + penalty_factor * v
+    #[value(name = "epsilon")]
+    Epsilon // violations up to a level falling to 0 at generation epsilon_generations count as feasible
+}
+
+/// Read-only population handed to a `DeProgressCallback`, only valid during the call
+#[repr(C)]
+pub struct DePopulationView {
+    pub members: *const Vector,
+    pub fitness: *const c_double,
+    pub size: u32
+}
+
+pub type DeProgressCallback = unsafe extern "C" fn(generation: u64, best_fitness: c_double, p_population: *const DePopulationView, p_user_data: *mut c_void) -> c_int;
+
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct DeConfig {
-    pub population_size: u32,
+    pub population_size: u32, // initial size with linear population size reduction
+    pub final_population_size: u32, // L-SHADE style linear reduction down to this size, 0 keeps the size constant
+    pub reduction_evaluations: u64, // evaluation budget over which the population shrinks to final_population_size
     pub crossover_probability: c_double,
     pub amplification_factor: c_double,
     pub lambda: c_double,
-    pub stop_condition: DeStopCondition
+    pub mutation_strategy: DeMutationStrategy,
+    pub p_best_fraction: c_double, // DE/current-to-pbest/1 specific
+    pub crossover_type: DeCrossoverType,
+    pub boundary_handling: DeBoundaryHandling,
+    pub initialization: DeInitialization,
+    pub adaptation: DeAdaptation,
+    pub adaptation_rate: c_double, // JADE specific c (0, 1]
+    pub history_size: u32, // SHADE specific number of memory slots
+    pub jde_tau1: c_double, // jDE specific probability of regenerating F
+    pub jde_tau2: c_double, // jDE specific probability of regenerating CR
+    pub archive_rate: c_double, // external archive capacity in multiples of population_size, 0 disables it (only filled while DE/current-to-pbest/1 can be selected)
+    pub strategy_pool: u32, // Sade/Epsde specific `DeMutationStrategy::pool_bit`s, 0 means rand/1, rand/2, best/2 and current-to-best/1
+    pub learning_period: u32, // Sade/Epsde specific generations the success rates are counted over
+    pub selection: DeSelection,
+    pub constraint_handling: DeConstraintHandling,
+    pub penalty_factor: c_double, // Penalty specific weight of the violation
+    pub equality_tolerance: c_double, // |h| up to this counts as satisfied
+    pub epsilon_generations: u64, // Epsilon specific generation at which the level reaches 0, about a fifth of the run (the population tends to collapse onto the relaxed optimum otherwise)
+    pub epsilon_exponent: c_double, // Epsilon specific cp of the level v_0 (1 - t / epsilon_generations)^cp
+    pub stop_condition: DeStopCondition,
+    pub extra_stop_conditions: [DeStopCondition; DE_MAX_EXTRA_STOP_CONDITIONS], // combined with stop_condition
+    pub extra_stop_condition_count: u32,
+    pub stop_combination: DeStopCombination,
+    pub max_generations: u64, // hard cap on top of the stop conditions, 0 means no cap (only valid if the stop conditions are bounded by themselves)
+    pub seed: u64, // 0 picks a fresh non-reproducible seed for every run
+    pub record_history: bool,
+    pub thread_count: u32, // threads evaluating the population, needs `minimize_parallel` (or a thread-safe C objective) above 1
+    pub progress_callback: Option<DeProgressCallback>, // raw C callback, see `minimize_with_progress` and `DeOptimizer::set_progress` for closures
+    pub progress_user_data: *mut c_void
+}
+
+// The progress user data is only dereferenced by the callback it was set up with
+unsafe impl Send for DeConfig {}
+unsafe impl Sync for DeConfig {}
+
+impl DeConfig {
+    /// Combines `conditions` with `stop_condition`, panics if there are more than `DE_MAX_EXTRA_STOP_CONDITIONS`
+    pub fn set_extra_stop_conditions(&mut self, conditions: &[DeStopCondition], combination: DeStopCombination) {
+        if conditions.len() > DE_MAX_EXTRA_STOP_CONDITIONS {
+            panic!("At most {} extra stop conditions are supported", DE_MAX_EXTRA_STOP_CONDITIONS);
+        }
+        self.extra_stop_conditions[..conditions.len()].copy_from_slice(conditions);
+        self.extra_stop_condition_count = conditions.len() as u32;
+        self.stop_combination = combination;
+    }
+
+    /// Sets the strategies the ensembles choose from, an empty slice selects the default pool
+    pub fn set_strategy_pool(&mut self, strategies: &[DeMutationStrategy]) {
+        self.strategy_pool = strategies.iter().fold(0, |pool, strategy| pool | strategy.pool_bit());
+    }
+}
+
+impl Default for DeConfig {
+    fn default() -> Self {
+        return Self {
+            population_size: 20,
+            final_population_size: 0,
+            reduction_evaluations: 0,
+            crossover_probability: 0.9,
+            amplification_factor: 0.5,
+            lambda: 0.5,
+            mutation_strategy: DeMutationStrategy::RandBest1,
+            p_best_fraction: 0.1,
+            crossover_type: DeCrossoverType::Binomial,
+            boundary_handling: DeBoundaryHandling::Clamp,
+            initialization: DeInitialization::Uniform,
+            adaptation: DeAdaptation::None,
+            adaptation_rate: 0.1,
+            history_size: 6,
+            jde_tau1: 0.1,
+            jde_tau2: 0.1,
+            archive_rate: 0.0,
+            strategy_pool: 0,
+            learning_period: 50,
+            selection: DeSelection::Parent,
+            constraint_handling: DeConstraintHandling::FeasibilityRules,
+            penalty_factor: 1e6,
+            equality_tolerance: 1e-4,
+            epsilon_generations: 20,
+            epsilon_exponent: 5.0,
+            stop_condition: DeStopCondition::after_iters(100),
+            extra_stop_conditions: [DeStopCondition::after_iters(0); DE_MAX_EXTRA_STOP_CONDITIONS],
+            extra_stop_condition_count: 0,
+            stop_combination: DeStopCombination::Any,
+            max_generations: 0,
+            seed: 0,
+            record_history: false,
+            thread_count: 1,
+            progress_callback: None,
+            progress_user_data: std::ptr::null_mut()
+        };
+    }
+}
+
+/// Which islands exchange their best members
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DeMigrationTopology {
+    #[value(name = "ring")]
+    Ring, // island i sends to island i + 1
+    #[value(name = "full")]
+    FullyConnected // every island sends to every other island
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct DeIslandConfig {
+    pub island_count: u32, // populations of DeConfig::population_size evolving in their own threads
+    pub migration_interval: u32, // generations between migrations
+    pub migration_count: u32, // best members sent along each edge of the topology, replacing the receiver's worst, 0 isolates the islands
+    pub topology: DeMigrationTopology
+}
+
+impl Default for DeIslandConfig {
+    fn default() -> Self {
+        return Self {
+            island_count: 4,
+            migration_interval: 20,
+            migration_count: 1,
+            topology: DeMigrationTopology::Ring
+        };
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeStopReason {
+    ItersExhausted,
+    AccuracyReached,
+    EvaluationsExhausted,
+    Stagnated,
+    Converged,
+    TimeLimitReached,
+    Cancelled // finished before the stop conditions held
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeStatus {
+    Ok,
+    NullArgument,
+    InvalidDimensions,
+    InvalidBounds,
+    InvalidPopulationSize,
+    InvalidCrossoverProbability,
+    InvalidAmplificationFactor,
+    InvalidLambda,
+    InvalidPBestFraction,
+    InvalidStrategy,
+    InvalidStopCondition,
+    InvalidInitialPopulation,
+    InvalidInitialization,
+    InvalidAdaptation,
+    InvalidConfigChange,
+    InvalidThreadCount,
+    InvalidConstraintHandling,
+    InvalidVariableTypes,
+    InvalidIslandConfig,
+    InvalidNicheRadius
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeError {
+    NullArgument,
+    InvalidDimensions,
+    InvalidBounds,
+    InvalidPopulationSize,
+    InvalidCrossoverProbability,
+    InvalidAmplificationFactor,
+    InvalidLambda,
+    InvalidPBestFraction,
+    InvalidStrategy,
+    InvalidStopCondition,
+    InvalidInitialPopulation,
+    InvalidInitialization,
+    InvalidAdaptation,
+    InvalidConfigChange,
+    InvalidThreadCount,
+    InvalidConstraintHandling,
+    InvalidVariableTypes,
+    InvalidIslandConfig,
+    InvalidNicheRadius
+}
+
+impl DeStatus {
+    pub fn to_result(self) -> Result<(), DeError> {
+        return match self {
+            DeStatus::Ok => Ok(()),
+            DeStatus::NullArgument => Err(DeError::NullArgument),
+            DeStatus::InvalidDimensions => Err(DeError::InvalidDimensions),
+            DeStatus::InvalidBounds => Err(DeError::InvalidBounds),
+            DeStatus::InvalidPopulationSize => Err(DeError::InvalidPopulationSize),
+            DeStatus::InvalidCrossoverProbability => Err(DeError::InvalidCrossoverProbability),
+            DeStatus::InvalidAmplificationFactor => Err(DeError::InvalidAmplificationFactor),
+            DeStatus::InvalidLambda => Err(DeError::InvalidLambda),
+            DeStatus::InvalidPBestFraction => Err(DeError::InvalidPBestFraction),
+            DeStatus::InvalidStrategy => Err(DeError::InvalidStrategy),
+            DeStatus::InvalidStopCondition => Err(DeError::InvalidStopCondition),
+            DeStatus::InvalidInitialPopulation => Err(DeError::InvalidInitialPopulation),
+            DeStatus::InvalidInitialization => Err(DeError::InvalidInitialization),
+            DeStatus::InvalidAdaptation => Err(DeError::InvalidAdaptation),
+            DeStatus::InvalidConfigChange => Err(DeError::InvalidConfigChange),
+            DeStatus::InvalidThreadCount => Err(DeError::InvalidThreadCount),
+            DeStatus::InvalidConstraintHandling => Err(DeError::InvalidConstraintHandling),
+            DeStatus::InvalidVariableTypes => Err(DeError::InvalidVariableTypes),
+            DeStatus::InvalidIslandConfig => Err(DeError::InvalidIslandConfig),
+            DeStatus::InvalidNicheRadius => Err(DeError::InvalidNicheRadius)
+        };
+    }
+}
+
+impl fmt::Display for DeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            DeError::NullArgument => "null target, config, objective or result, or constraints without a constraint function",
+            DeError::InvalidDimensions => "the number of dimensions (and objectives) must be at least 1",
+            DeError::InvalidBounds => "bounds must be finite with the left bound below the right bound",
+            DeError::InvalidPopulationSize => "the (final) population is too small for the mutation strategy (or one of the pool), or the population reduction is invalid",
+            DeError::InvalidCrossoverProbability => "the crossover probability must be within [0, 1]",
+            DeError::InvalidAmplificationFactor => "the amplification factor must be within [0, 2]",
+            DeError::InvalidLambda => "lambda must be within [0, 1]",
+            DeError::InvalidPBestFraction => "the p-best fraction must be within (0, 1]",
+            DeError::InvalidStrategy => "unknown mutation strategy, crossover type, boundary handling or selection",
+            DeError::InvalidStopCondition => "invalid stop condition, stop conditions that might never hold without max_generations, or a fitness-based condition in a multi-objective run",
+            DeError::InvalidInitialPopulation => "the initial population is larger than the population, has rows of the wrong length, leaves the bounds or has non-integer discrete coordinates",
+            DeError::InvalidInitialization => "unknown initialization, Sobol initialization with more than DE_SOBOL_MAX_DIMENSIONS dimensions, or opposition in a multi-objective run",
+            DeError::InvalidAdaptation => "unknown adaptation, adaptation rate outside (0, 1], zero SHADE history size, jDE tau outside [0, 1], archive rate outside [0, 10], unknown strategy pool bits or zero learning period",
+            DeError::InvalidConfigChange => "the population size, final population size, adaptation, history size, archive rate, learning period and strategy pool cannot change during a run",
+            DeError::InvalidThreadCount => "more than one thread needs a thread-safe objective (see minimize_parallel), islands need a thread count of at most 1",
+            DeError::InvalidConstraintHandling => "unknown constraint handling, negative equality tolerance, penalty factor outside (0, inf) or non-positive epsilon parameters",
+            DeError::InvalidVariableTypes => "the variable types do not match the dimensions, or an integer or categorical coordinate has no integer within its bounds",
+            DeError::InvalidIslandConfig => "no islands, zero migration interval, or as many immigrants per migration as an island has members",
+            DeError::InvalidNicheRadius => "the niche radius must be finite and non-negative"
+        };
+        return write!(f, "{}", message);
+    }
+}
+
+impl std::error::Error for DeError {}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct DeGenerationStats {
+    pub best_fitness: c_double,
+    pub mean_fitness: c_double
+}
+
+/// Result as returned by `de_minimum`, its buffers are owned by C and released with `de_result_free`
+#[repr(C)]
+pub struct DeRawResult {
+    pub best: Vector,
+    pub best_fitness: c_double,
+    pub generations: u64,
+    pub evaluations: u64,
+    pub stop_reason: DeStopReason,
+    pub history: *mut DeGenerationStats,
+    pub history_length: u64,
+    pub best_violation: c_double,
+    pub is_feasible: u8,
+    pub strategy_trials: [u64; DE_MUTATION_STRATEGY_COUNT], // indexed by `DeMutationStrategy as usize`
+    pub strategy_successes: [u64; DE_MUTATION_STRATEGY_COUNT]
 }
 
+/// How often a mutation strategy was used during a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeStrategyUsage {
+    pub strategy: DeMutationStrategy,
+    pub trials: u64,
+    pub successes: u64 // trials that replaced their parent
+}
+
+#[derive(Debug, Clone)]
+pub struct DeResult {
+    pub best: Vec<f64>,
+    pub best_fitness: f64,
+    pub generations: u64,
+    pub evaluations: u64,
+    pub stop_reason: DeStopReason,
+    pub history: Option<Vec<DeGenerationStats>>, // Some if `DeConfig::record_history` was set
+    pub best_violation: f64, // total constraint violation of `best`, 0 without constraints
+    pub is_feasible: bool,
+    pub strategy_usage: Vec<DeStrategyUsage> // the strategies with at least one trial
+}
+
+impl DeResult {
+    /// Copies `raw` into Rust-owned memory and frees the C buffers
+    ///
+    /// # Safety
+    /// `raw` must have been filled by a successful `de_minimum` call and must not be used or freed afterwards
+    pub unsafe fn from_raw(mut raw: DeRawResult) -> Self {
+        let best = std::slice::from_raw_parts(raw.best.coordinates, raw.best.num_dimensions as usize).to_vec();
+        let history = if raw.history.is_null() {
+            None
+        } else {
+            Some(std::slice::from_raw_parts(raw.history, raw.history_length as usize).to_vec())
+        };
+        let result = Self {
+            best,
+            best_fitness: raw.best_fitness,
+            generations: raw.generations,
+            evaluations: raw.evaluations,
+            stop_reason: raw.stop_reason,
+            history,
+            best_violation: raw.best_violation,
+            is_feasible: raw.is_feasible != 0,
+            strategy_usage: DeMutationStrategy::ALL.iter()
+                .filter(|&&strategy| raw.strategy_trials[strategy as usize] > 0)
+                .map(|&strategy| DeStrategyUsage { strategy, trials: raw.strategy_trials[strategy as usize], successes: raw.strategy_successes[strategy as usize] })
+                .collect()
+        };
+        de_result_free(&mut raw);
+        return result;
+    }
+
+    /// The best vector as a fixed-size `VectorN`, `N` has to match the number of dimensions
+    pub fn best_vector<const N: usize>(&self) -> VectorN<N> {
+        return VectorN::<N>::new(self.best.as_slice().try_into().expect("Dimension mismatch"));
+    }
+}
+
+/// Evaluates `count` row-major vectors of `num_dimensions` coordinates into `count` fitness values
+pub type DeBatchFunction = unsafe extern "C" fn(p_population: *const c_double, count: u32, num_dimensions: u32, p_fitness: *mut c_double, p_user_data: *mut c_void);
+
+/// Writes the inequality values g (feasible if <= 0) followed by the equality values h (feasible if = 0) of a vector
+pub type DeConstraintFunction = unsafe extern "C" fn(input: Vector, p_values: *mut c_double, p_user_data: *mut c_void);
+
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct DeOptimizationTarget {
-    pub f: unsafe extern "C" fn(Vector, *mut c_void) -> c_double,
+    pub f: Option<unsafe extern "C" fn(Vector, *mut c_void) -> c_double>, // may be None if f_batch is given
+    pub f_batch: Option<DeBatchFunction>,
+    pub num_dimensions: u32,
+    pub left_bounds: *const c_double, // num_dimensions entries
+    pub right_bounds: *const c_double, // num_dimensions entries
+    pub initial_population: *const c_double, // optional, initial_population_size rows of num_dimensions (row-major)
+    pub initial_population_size: u32,
+    pub is_thread_safe: u8, // 1 if f may run concurrently on the shared user data
+    pub thread_user_data: *const *mut c_void, // optional, thread_count per-thread user data pointers
+    pub g: Option<DeConstraintFunction>, // optional, None for an unconstrained target
+    pub num_inequality_constraints: u32,
+    pub num_equality_constraints: u32,
+    pub variable_types: *const DeVariableType // optional, num_dimensions entries, null treats every coordinate as continuous
+}
+
+/// Writes the `num_objectives` values of a vector, all of them are minimized
+pub type DeMultiFunction = unsafe extern "C" fn(input: Vector, p_objectives: *mut c_double, p_user_data: *mut c_void);
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DeMultiOptimizationTarget {
+    pub f: Option<DeMultiFunction>,
+    pub num_objectives: u32,
+    pub num_dimensions: u32,
+    pub left_bounds: *const c_double, // num_dimensions entries
+    pub right_bounds: *const c_double, // num_dimensions entries
+    pub variable_types: *const DeVariableType // optional, num_dimensions entries
+}
+
+/// Pareto front as returned by `de_pareto_minimum`, its buffers are owned by C and released with `de_pareto_front_free`
+#[repr(C)]
+pub struct DeRawParetoFront {
+    pub size: u32,
     pub num_dimensions: u32,
-    pub left_bound: c_double,
-    pub right_bound: c_double
+    pub num_objectives: u32,
+    pub coordinates: *mut c_double, // size rows of num_dimensions, sorted by the first objective
+    pub objectives: *mut c_double, // size rows of num_objectives
+    pub generations: u64,
+    pub evaluations: u64,
+    pub stop_reason: DeStopReason
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeParetoPoint {
+    pub coordinates: Vec<f64>,
+    pub objectives: Vec<f64>
+}
+
+#[derive(Debug, Clone)]
+pub struct DeParetoFront {
+    pub points: Vec<DeParetoPoint>, // non-dominated, sorted by the first objective
+    pub generations: u64,
+    pub evaluations: u64,
+    pub stop_reason: DeStopReason
+}
+
+impl DeParetoFront {
+    /// Copies `raw` into Rust-owned memory and frees the C buffers
+    ///
+    /// # Safety
+    /// `raw` must have been filled by a successful `de_pareto_minimum` call and must not be used or freed afterwards
+    pub unsafe fn from_raw(mut raw: DeRawParetoFront) -> Self {
+        let size = raw.size as usize;
+        let coordinates = std::slice::from_raw_parts(raw.coordinates, size * raw.num_dimensions as usize);
+        let objectives = std::slice::from_raw_parts(raw.objectives, size * raw.num_objectives as usize);
+        let points = coordinates.chunks(raw.num_dimensions as usize).zip(objectives.chunks(raw.num_objectives as usize))
+            .map(|(coordinates, objectives)| DeParetoPoint { coordinates: coordinates.to_vec(), objectives: objectives.to_vec() })
+            .collect();
+        let front = Self {
+            points,
+            generations: raw.generations,
+            evaluations: raw.evaluations,
+            stop_reason: raw.stop_reason
+        };
+        de_pareto_front_free(&mut raw);
+        return front;
+    }
+}
+
+/// Distinct optima as returned by `de_distinct_optima`, its buffers are owned by C and released with `de_optima_free`
+#[repr(C)]
+pub struct DeRawOptima {
+    pub size: u32,
+    pub num_dimensions: u32,
+    pub coordinates: *mut c_double, // size rows of num_dimensions, best first
+    pub fitness: *mut c_double,
+    pub violation: *mut c_double,
+    pub generations: u64,
+    pub evaluations: u64,
+    pub stop_reason: DeStopReason
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeOptimum {
+    pub coordinates: Vec<f64>,
+    pub fitness: f64,
+    pub violation: f64 // total constraint violation, 0 without constraints
+}
+
+#[derive(Debug, Clone)]
+pub struct DeOptima {
+    pub optima: Vec<DeOptimum>, // pairwise further apart than the radius, best first
+    pub generations: u64,
+    pub evaluations: u64,
+    pub stop_reason: DeStopReason
+}
+
+impl DeOptima {
+    /// Copies `raw` into Rust-owned memory and frees the C buffers
+    ///
+    /// # Safety
+    /// `raw` must have been filled by a successful `de_distinct_optima` or `de_niching_minimum` call and must not be used or freed afterwards
+    pub unsafe fn from_raw(mut raw: DeRawOptima) -> Self {
+        let size = raw.size as usize;
+        let coordinates = std::slice::from_raw_parts(raw.coordinates, size * raw.num_dimensions as usize);
+        let fitness = std::slice::from_raw_parts(raw.fitness, size);
+        let violation = std::slice::from_raw_parts(raw.violation, size);
+        let optima = coordinates.chunks(raw.num_dimensions as usize).zip(fitness).zip(violation)
+            .map(|((coordinates, &fitness), &violation)| DeOptimum { coordinates: coordinates.to_vec(), fitness, violation })
+            .collect();
+        let result = Self {
+            optima,
+            generations: raw.generations,
+            evaluations: raw.evaluations,
+            stop_reason: raw.stop_reason
+        };
+        de_optima_free(&mut raw);
+        return result;
+    }
+}
+
+/// Opaque state of a stepwise run, owned by C
+#[repr(C)]
+pub struct DeState {
+    _private: [u8; 0]
 }
 
 //#[link(name = "differential_evolution")]
 #[link(name = "differential_evolution_cmake")]
 extern "C" {
-   pub fn de_minimum(pOptimizationTarget: *mut DeOptimizationTarget, pConfig: *mut DeConfig, pUserData: *mut c_void) -> Vector;
+   pub fn de_minimum(pOptimizationTarget: *mut DeOptimizationTarget, pConfig: *mut DeConfig, pUserData: *mut c_void, pResult: *mut DeRawResult) -> DeStatus;
+   pub fn de_validate(pOptimizationTarget: *mut DeOptimizationTarget, pConfig: *mut DeConfig) -> DeStatus;
    //pub fn de_minimum_stub(pOptimizationTarget: *mut DeOptimizationTarget, pConfig: *mut DeConfig) -> Vector;
    pub fn de_vector_allocate_coordinates (pVector: *mut Vector);
    pub fn de_vector_free_coordinates (pVector: *mut Vector);
-}
\ No newline at end of file
+   pub fn de_result_free (pResult: *mut DeRawResult);
+   pub fn de_seed_for_stream (baseSeed: u64, streamIndex: u64) -> u64;
+   pub fn de_mutation_donor_count (strategy: DeMutationStrategy) -> u32;
+   pub fn de_init(pOptimizationTarget: *mut DeOptimizationTarget, pConfig: *mut DeConfig, pUserData: *mut c_void, ppState: *mut *mut DeState) -> DeStatus;
+   pub fn de_step(pState: *mut DeState) -> u8;
+   pub fn de_best(pState: *mut DeState, pCoordinates: *mut c_double) -> c_double;
+   pub fn de_best_violation(pState: *mut DeState) -> c_double;
+   pub fn de_population_size(pState: *mut DeState) -> u32;
+   pub fn de_population(pState: *mut DeState, pCoordinates: *mut c_double, pFitness: *mut c_double);
+   pub fn de_generations(pState: *mut DeState) -> u64;
+   pub fn de_evaluations(pState: *mut DeState) -> u64;
+   pub fn de_set_config(pState: *mut DeState, pConfig: *mut DeConfig) -> DeStatus;
+   pub fn de_finish(pState: *mut DeState, pResult: *mut DeRawResult);
+   pub fn de_island_minimum(pOptimizationTarget: *mut DeOptimizationTarget, pConfig: *mut DeConfig, pIslandConfig: *mut DeIslandConfig, pUserData: *mut c_void, pResult: *mut DeRawResult) -> DeStatus;
+   pub fn de_pareto_minimum(pOptimizationTarget: *mut DeMultiOptimizationTarget, pConfig: *mut DeConfig, pUserData: *mut c_void, pFront: *mut DeRawParetoFront) -> DeStatus;
+   pub fn de_pareto_front_free(pFront: *mut DeRawParetoFront);
+   pub fn de_distinct_optima(pState: *mut DeState, radius: c_double, pOptima: *mut DeRawOptima) -> DeStatus;
+   pub fn de_optima_free(pOptima: *mut DeRawOptima);
+   pub fn de_niching_minimum(pOptimizationTarget: *mut DeOptimizationTarget, pConfig: *mut DeConfig, radius: c_double, pUserData: *mut c_void, pOptima: *mut DeRawOptima) -> DeStatus;
+}
+
+/// Derives a non-zero `DeConfig::seed` for stream `stream_index` (e.g. a thread or try number) from `base_seed`,
+/// so that parallel runs get distinct but reproducible random streams
+pub fn seed_for_stream(base_seed: u64, stream_index: u64) -> u64 {
+    return unsafe { de_seed_for_stream(base_seed, stream_index) };
+}
+
+/// Search space of `minimize`, a `(lower, upper)` range per coordinate
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeBounds {
+    pub left_bounds: Vec<f64>,
+    pub right_bounds: Vec<f64>,
+    pub variable_types: Vec<DeVariableType> // one per coordinate, empty means all continuous
+}
+
+impl DeBounds {
+    /// The same `(lower, upper)` range for each of the `num_dimensions` coordinates
+    pub fn uniform(num_dimensions: usize, bounds: (f64, f64)) -> Self {
+        return Self {
+            left_bounds: vec![bounds.0; num_dimensions],
+            right_bounds: vec![bounds.1; num_dimensions],
+            variable_types: Vec::new()
+        };
+    }
+
+    /// One `(lower, upper)` range per coordinate
+    pub fn per_dimension(bounds: &[(f64, f64)]) -> Self {
+        return Self {
+            left_bounds: bounds.iter().map(|b| b.0).collect(),
+            right_bounds: bounds.iter().map(|b| b.1).collect(),
+            variable_types: Vec::new()
+        };
+    }
+
+    /// Sets the type of every coordinate, integer and categorical ones take the integers within their bounds
+    pub fn with_variable_types(mut self, variable_types: &[DeVariableType]) -> Self {
+        self.variable_types = variable_types.to_vec();
+        return self;
+    }
+
+    pub fn num_dimensions(&self) -> usize {
+        return self.left_bounds.len();
+    }
+
+    /// The checks C cannot do on the raw pointers, the ranges themselves are validated by C
+    fn validate(&self) -> Result<(), DeError> {
+        if self.left_bounds.len() != self.right_bounds.len() {
+            return Err(DeError::InvalidBounds);
+        }
+        if !self.variable_types.is_empty() && self.variable_types.len() != self.num_dimensions() {
+            return Err(DeError::InvalidVariableTypes);
+        }
+        return Ok(());
+    }
+
+    fn variable_types_ptr(&self) -> *const DeVariableType {
+        return if self.variable_types.is_empty() { std::ptr::null() } else { self.variable_types.as_ptr() };
+    }
+}
+
+type Objective<'a> = Box<dyn FnMut(&[f64]) -> f64 + 'a>;
+type BatchObjective<'a> = Box<dyn FnMut(&[f64], &mut [f64]) + 'a>;
+type ParallelObjective<'a> = Box<dyn Fn(&[f64]) -> f64 + Sync + 'a>;
+
+enum DeObjective<'a> {
+    Single(Objective<'a>),
+    Batch(BatchObjective<'a>),
+    Parallel(ParallelObjective<'a>)
+}
+
+type ConstraintClosure<'a> = Box<dyn FnMut(&[f64], &mut [f64]) + 'a>;
+
+/// Constraints of a closure objective, `g` writes `num_inequality` inequality values followed by `num_equality` equality values
+struct DeConstraints<'a> {
+    g: ConstraintClosure<'a>,
+    num_inequality: u32,
+    num_equality: u32
+}
+
+struct ClosureUserData<'a> {
+    f: DeObjective<'a>,
+    constraints: Option<DeConstraints<'a>>,
+    panic_payload: Mutex<Option<Box<dyn Any + Send>>> // the first panic of any evaluation thread
+}
+
+unsafe extern "C" fn closure_trampoline(input: Vector, p_user_data: *mut c_void) -> c_double {
+    let user_data = &mut *(p_user_data as *mut ClosureUserData);
+    let panic_payload = user_data.panic_payload.get_mut().unwrap();
+    if panic_payload.is_some() {
+        return f64::INFINITY; // Let the C side run out, the panic is resumed once it returns
+    }
+    let DeObjective::Single(f) = &mut user_data.f else {
+        unreachable!("single evaluation of a batched or parallel objective");
+    };
+    let coordinates = std::slice::from_raw_parts(input.coordinates, input.num_dimensions as usize);
+    match panic::catch_unwind(AssertUnwindSafe(|| f(coordinates))) {
+        Ok(value) => return value,
+        Err(payload) => {
+            *panic_payload = Some(payload);
+            return f64::INFINITY;
+        }
+    }
+}
+
+/// Called from several threads at once, so only shared references to the user data are taken
+unsafe extern "C" fn parallel_trampoline(input: Vector, p_user_data: *mut c_void) -> c_double {
+    let user_data = &*(p_user_data as *const ClosureUserData);
+    if user_data.panic_payload.lock().unwrap().is_some() {
+        return f64::INFINITY;
+    }
+    let DeObjective::Parallel(f) = &user_data.f else {
+        unreachable!("parallel evaluation of a single or batched objective");
+    };
+    let coordinates = std::slice::from_raw_parts(input.coordinates, input.num_dimensions as usize);
+    match panic::catch_unwind(AssertUnwindSafe(|| f(coordinates))) {
+        Ok(value) => return value,
+        Err(payload) => {
+            user_data.panic_payload.lock().unwrap().get_or_insert(payload);
+            return f64::INFINITY;
+        }
+    }
+}
+
+unsafe extern "C" fn batch_trampoline(p_population: *const c_double, count: u32, num_dimensions: u32, p_fitness: *mut c_double, p_user_data: *mut c_void) {
+    let user_data = &mut *(p_user_data as *mut ClosureUserData);
+    let panic_payload = user_data.panic_payload.get_mut().unwrap();
+    let fitness = std::slice::from_raw_parts_mut(p_fitness, count as usize);
+    if panic_payload.is_some() {
+        fitness.fill(f64::INFINITY);
+        return;
+    }
+    let DeObjective::Batch(f) = &mut user_data.f else {
+        unreachable!("batch evaluation of a single or parallel objective");
+    };
+    let population = std::slice::from_raw_parts(p_population, count as usize * num_dimensions as usize);
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| f(population, &mut *fitness))) {
+        *panic_payload = Some(payload);
+        fitness.fill(f64::INFINITY);
+    }
+}
+
+unsafe extern "C" fn constraint_trampoline(input: Vector, p_values: *mut c_double, p_user_data: *mut c_void) {
+    let user_data = &mut *(p_user_data as *mut ClosureUserData);
+    let panic_payload = user_data.panic_payload.get_mut().unwrap();
+    let constraints = user_data.constraints.as_mut().expect("constraint evaluation without constraints");
+    let values = std::slice::from_raw_parts_mut(p_values, (constraints.num_inequality + constraints.num_equality) as usize);
+    if panic_payload.is_some() {
+        values.fill(f64::INFINITY);
+        return;
+    }
+    let coordinates = std::slice::from_raw_parts(input.coordinates, input.num_dimensions as usize);
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| (constraints.g)(coordinates, &mut *values))) {
+        *panic_payload = Some(payload);
+        values.fill(f64::INFINITY);
+    }
+}
+
+/// Validates `bounds` and builds the target calling `objective` (and `constraints`) through the trampolines with the returned user data,
+/// without an initial population; the target points into `bounds`, so they have to outlive the run
+fn closure_target<'a>(objective: DeObjective<'a>, constraints: Option<DeConstraints<'a>>, bounds: &DeBounds) -> Result<(DeOptimizationTarget, Box<ClosureUserData<'a>>), DeError> {
+    bounds.validate()?;
+    let (f, f_batch): (Option<unsafe extern "C" fn(Vector, *mut c_void) -> c_double>, Option<DeBatchFunction>) = match objective {
+        DeObjective::Single(_) => (Some(closure_trampoline), None),
+        DeObjective::Batch(_) => (None, Some(batch_trampoline)),
+        DeObjective::Parallel(_) => (Some(parallel_trampoline), None)
+    };
+    let is_thread_safe = matches!(objective, DeObjective::Parallel(_));
+    let (num_inequality_constraints, num_equality_constraints) = constraints.as_ref().map_or((0, 0), |constraints| (constraints.num_inequality, constraints.num_equality));
+    let g: Option<DeConstraintFunction> = if constraints.is_some() { Some(constraint_trampoline) } else { None };
+    let target = DeOptimizationTarget {
+        f,
+        f_batch,
+        num_dimensions: bounds.num_dimensions() as u32,
+        left_bounds: bounds.left_bounds.as_ptr(),
+        right_bounds: bounds.right_bounds.as_ptr(),
+        initial_population: std::ptr::null(),
+        initial_population_size: 0,
+        is_thread_safe: is_thread_safe as u8,
+        thread_user_data: std::ptr::null(),
+        g,
+        num_inequality_constraints,
+        num_equality_constraints,
+        variable_types: bounds.variable_types_ptr()
+    };
+    let user_data = Box::new(ClosureUserData {
+        f: objective,
+        constraints,
+        panic_payload: Mutex::new(None)
+    });
+    return Ok((target, user_data));
+}
+
+type MultiObjective<'a> = Box<dyn FnMut(&[f64], &mut [f64]) + 'a>;
+
+struct MultiObjectiveUserData<'a> {
+    f: MultiObjective<'a>,
+    num_objectives: usize,
+    panic_payload: Option<Box<dyn Any + Send>>
+}
+
+unsafe extern "C" fn multi_objective_trampoline(input: Vector, p_objectives: *mut c_double, p_user_data: *mut c_void) {
+    let user_data = &mut *(p_user_data as *mut MultiObjectiveUserData);
+    let objectives = std::slice::from_raw_parts_mut(p_objectives, user_data.num_objectives);
+    if user_data.panic_payload.is_some() {
+        objectives.fill(f64::INFINITY);
+        return;
+    }
+    let coordinates = std::slice::from_raw_parts(input.coordinates, input.num_dimensions as usize);
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| (user_data.f)(coordinates, &mut *objectives))) {
+        user_data.panic_payload = Some(payload);
+        objectives.fill(f64::INFINITY);
+    }
+}
+
+/// What a progress closure gets to see after every generation
+pub struct DeProgress<'p> {
+    pub generation: u64,
+    pub best_fitness: f64,
+    members: &'p [Vector],
+    fitness: &'p [f64]
+}
+
+impl DeProgress<'_> {
+    pub fn population_size(&self) -> usize {
+        return self.members.len();
+    }
+
+    /// Coordinates of population member `index`
+    pub fn member(&self, index: usize) -> &[f64] {
+        let member = &self.members[index];
+        return unsafe { std::slice::from_raw_parts(member.coordinates, member.num_dimensions as usize) };
+    }
+
+    /// Fitness of every population member
+    pub fn fitness(&self) -> &[f64] {
+        return self.fitness;
+    }
+}
+
+type ProgressClosure<'a> = Box<dyn FnMut(&DeProgress) -> ControlFlow<()> + 'a>;
+
+struct ProgressUserData<'a> {
+    progress: ProgressClosure<'a>,
+    panic_payload: Option<Box<dyn Any + Send>>
+}
+
+unsafe extern "C" fn progress_trampoline(generation: u64, best_fitness: c_double, p_population: *const DePopulationView, p_user_data: *mut c_void) -> c_int {
+    let user_data = &mut *(p_user_data as *mut ProgressUserData);
+    let population = &*p_population;
+    let progress = DeProgress {
+        generation,
+        best_fitness,
+        members: std::slice::from_raw_parts(population.members, population.size as usize),
+        fitness: std::slice::from_raw_parts(population.fitness, population.size as usize)
+    };
+    match panic::catch_unwind(AssertUnwindSafe(|| (user_data.progress)(&progress))) {
+        Ok(ControlFlow::Continue(())) => return 0,
+        Ok(ControlFlow::Break(())) => return 1,
+        Err(payload) => {
+            user_data.panic_payload = Some(payload);
+            return 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeMember {
+    pub coordinates: Vec<f64>,
+    pub fitness: f64
+}
+
+/// Stepwise differential evolution: the caller runs the generations one by one and may inspect the population,
+/// change the config or stop at any point in between
+///
+/// A panic inside `f` is propagated once the current step has returned
+pub struct DeOptimizer<'a> {
+    state: *mut DeState,
+    user_data: Box<ClosureUserData<'a>>, // boxed, C keeps a pointer to it
+    progress_user_data: Option<Box<ProgressUserData<'a>>>, // likewise
+    bounds: DeBounds, // C keeps pointers into it
+    config: DeConfig
+}
+
+impl<'a> DeOptimizer<'a> {
+    /// Creates and evaluates the initial population of `minimize(f, bounds, config)`
+    pub fn new<F: FnMut(&[f64]) -> f64 + 'a>(f: F, bounds: &DeBounds, config: &DeConfig) -> Result<Self, DeError> {
+        return Self::new_warm_start::<F, Vec<f64>>(f, bounds, config, &[]);
+    }
+
+    /// Creates and evaluates the initial population of `minimize_warm_start(f, bounds, config, initial_population)`
+    pub fn new_warm_start<F: FnMut(&[f64]) -> f64 + 'a, V: AsRef<[f64]>>(f: F, bounds: &DeBounds, config: &DeConfig, initial_population: &[V]) -> Result<Self, DeError> {
+        return Self::with_objective(DeObjective::Single(Box::new(f)), None, bounds, config, initial_population);
+    }
+
+    /// Creates and evaluates the initial population of `minimize_parallel(f, bounds, config)`
+    pub fn new_parallel<F: Fn(&[f64]) -> f64 + Sync + 'a>(f: F, bounds: &DeBounds, config: &DeConfig) -> Result<Self, DeError> {
+        return Self::with_objective::<Vec<f64>>(DeObjective::Parallel(Box::new(f)), None, bounds, config, &[]);
+    }
+
+    /// Creates and evaluates the initial population of `minimize_batched(f, bounds, config)`
+    pub fn new_batched<F: FnMut(&[f64], &mut [f64]) + 'a>(f: F, bounds: &DeBounds, config: &DeConfig) -> Result<Self, DeError> {
+        return Self::with_objective::<Vec<f64>>(DeObjective::Batch(Box::new(f)), None, bounds, config, &[]);
+    }
+
+    /// Creates and evaluates the initial population of `minimize_constrained(f, g, num_inequality, num_equality, bounds, config)`
+    pub fn new_constrained<F: FnMut(&[f64]) -> f64 + 'a, G: FnMut(&[f64], &mut [f64]) + 'a>(f: F, g: G, num_inequality: usize, num_equality: usize, bounds: &DeBounds, config: &DeConfig) -> Result<Self, DeError> {
+        let constraints = DeConstraints {
+            g: Box::new(g),
+            num_inequality: num_inequality as u32,
+            num_equality: num_equality as u32
+        };
+        return Self::with_objective::<Vec<f64>>(DeObjective::Single(Box::new(f)), Some(constraints), bounds, config, &[]);
+    }
+
+    fn with_objective<V: AsRef<[f64]>>(objective: DeObjective<'a>, constraints: Option<DeConstraints<'a>>, bounds: &DeBounds, config: &DeConfig, initial_population: &[V]) -> Result<Self, DeError> {
+        let bounds = bounds.clone(); // C keeps pointers into it, the vectors stay in place once it moves into the optimizer
+        let (mut target, user_data) = closure_target(objective, constraints, &bounds)?;
+        if initial_population.iter().any(|member| member.as_ref().len() != bounds.num_dimensions()) {
+            return Err(DeError::InvalidInitialPopulation);
+        }
+        let flat_initial_population = initial_population.iter().flat_map(|member| member.as_ref().iter().copied()).collect::<Vec<_>>();
+        target.initial_population = flat_initial_population.as_ptr();
+        target.initial_population_size = initial_population.len() as u32;
+        let mut optimizer = Self {
+            state: std::ptr::null_mut(),
+            user_data,
+            progress_user_data: None,
+            bounds,
+            config: *config
+        };
+        let p_user_data = &mut *optimizer.user_data as *mut ClosureUserData as *mut c_void;
+        unsafe {
+            de_init(&mut target, &mut optimizer.config, p_user_data, &mut optimizer.state).to_result()?;
+        }
+        optimizer.resume_panic();
+        return Ok(optimizer);
+    }
+
+    fn resume_panic(&mut self) {
+        if let Some(payload) = self.user_data.panic_payload.get_mut().unwrap().take() {
+            panic::resume_unwind(payload); // the state is released by drop
+        }
+        if let Some(payload) = self.progress_user_data.as_mut().and_then(|user_data| user_data.panic_payload.take()) {
+            panic::resume_unwind(payload);
+        }
+    }
+
+    /// Runs one generation, returns true once the stop conditions of the config hold
+    pub fn step(&mut self) -> bool {
+        let is_stopped = unsafe { de_step(self.state) } != 0;
+        self.resume_panic();
+        return is_stopped;
+    }
+
+    /// Steps until the stop conditions hold
+    pub fn run(&mut self) {
+        while !self.step() {}
+    }
+
+    /// The best member found so far
+    pub fn best(&self) -> DeMember {
+        let mut coordinates = vec![0.0; self.bounds.num_dimensions()];
+        let fitness = unsafe { de_best(self.state, coordinates.as_mut_ptr()) };
+        return DeMember { coordinates, fitness };
+    }
+
+    /// The total constraint violation of `best`, 0 if it is feasible
+    pub fn best_violation(&self) -> f64 {
+        return unsafe { de_best_violation(self.state) };
+    }
+
+    /// The current population, it shrinks over the run with a `final_population_size`
+    pub fn population(&self) -> Vec<DeMember> {
+        let num_dimensions = self.bounds.num_dimensions();
+        let size = unsafe { de_population_size(self.state) } as usize;
+        let mut coordinates = vec![0.0; size * num_dimensions];
+        let mut fitness = vec![0.0; size];
+        unsafe {
+            de_population(self.state, coordinates.as_mut_ptr(), fitness.as_mut_ptr());
+        }
+        return coordinates.chunks(num_dimensions).zip(fitness).map(|(member, fitness)| DeMember { coordinates: member.to_vec(), fitness }).collect();
+    }
+
+    pub fn generations(&self) -> u64 {
+        return unsafe { de_generations(self.state) };
+    }
+
+    pub fn evaluations(&self) -> u64 {
+        return unsafe { de_evaluations(self.state) };
+    }
+
+    /// The distinct optima of the current population: from the best member to the worst, each one further than `radius`
+    /// (Euclidean distance) from all kept before, best used with `DeSelection::Crowding`
+    pub fn distinct_optima(&self, radius: f64) -> Result<DeOptima, DeError> {
+        let mut raw = MaybeUninit::<DeRawOptima>::uninit();
+        unsafe { de_distinct_optima(self.state, radius, raw.as_mut_ptr()) }.to_result()?;
+        return Ok(unsafe { DeOptima::from_raw(raw.assume_init()) });
+    }
+
+    pub fn config(&self) -> &DeConfig {
+        return &self.config;
+    }
+
+    /// Replaces the config from the next step on, e.g. to change F, CR, the strategy or the stop conditions
+    ///
+    /// A progress closure set with `set_progress` stays in place
+    pub fn set_config(&mut self, config: &DeConfig) -> Result<(), DeError> {
+        let mut new_config = *config;
+        if self.progress_user_data.is_some() {
+            new_config.progress_callback = self.config.progress_callback;
+            new_config.progress_user_data = self.config.progress_user_data;
+        }
+        unsafe { de_set_config(self.state, &mut new_config) }.to_result()?;
+        self.config = new_config;
+        return Ok(());
+    }
+
+    /// Calls `progress` after every generation from the next step on, returning `ControlFlow::Break` stops the run
+    /// (`step` returns true and the stop reason is `Cancelled`)
+    pub fn set_progress<P: FnMut(&DeProgress) -> ControlFlow<()> + 'a>(&mut self, progress: P) {
+        let mut user_data = Box::new(ProgressUserData {
+            progress: Box::new(progress),
+            panic_payload: None
+        });
+        let mut config = self.config;
+        config.progress_callback = Some(progress_trampoline);
+        config.progress_user_data = &mut *user_data as *mut ProgressUserData as *mut c_void;
+        unsafe { de_set_config(self.state, &mut config) }.to_result().expect("The running config is valid");
+        self.config = config;
+        self.progress_user_data = Some(user_data); // the previous closure is only dropped once C no longer points to it
+    }
+
+    /// Ends the run, the stop reason is `Cancelled` unless the last step met the stop conditions
+    pub fn finish(mut self) -> DeResult {
+        let mut raw = MaybeUninit::<DeRawResult>::uninit();
+        unsafe {
+            de_finish(self.state, raw.as_mut_ptr());
+        }
+        self.state = std::ptr::null_mut();
+        return unsafe { DeResult::from_raw(raw.assume_init()) };
+    }
+}
+
+impl Drop for DeOptimizer<'_> {
+    fn drop(&mut self) {
+        if !self.state.is_null() {
+            unsafe { de_finish(self.state, std::ptr::null_mut()) };
+        }
+    }
+}
+
+/// Minimizes `f` over `bounds` with differential evolution
+///
+/// `f` may capture (and mutate) state; a panic inside it is propagated once the current generation has finished
+pub fn minimize<F: FnMut(&[f64]) -> f64>(f: F, bounds: &DeBounds, config: &DeConfig) -> Result<DeResult, DeError> {
+    return minimize_warm_start::<F, Vec<f64>>(f, bounds, config, &[]);
+}
+
+/// Like `minimize`, but the population starts with the `initial_population` vectors (e.g. the previous optimum)
+/// instead of random ones, the remaining members are still drawn uniformly
+pub fn minimize_warm_start<F: FnMut(&[f64]) -> f64, V: AsRef<[f64]>>(f: F, bounds: &DeBounds, config: &DeConfig, initial_population: &[V]) -> Result<DeResult, DeError> {
+    let mut optimizer = DeOptimizer::new_warm_start(f, bounds, config, initial_population)?;
+    optimizer.run();
+    return Ok(optimizer.finish());
+}
+
+/// Like `minimize`, but the population is evaluated by `config.thread_count` threads, so `f` has to be `Fn + Sync`
+pub fn minimize_parallel<F: Fn(&[f64]) -> f64 + Sync>(f: F, bounds: &DeBounds, config: &DeConfig) -> Result<DeResult, DeError> {
+    let mut optimizer = DeOptimizer::new_parallel(f, bounds, config)?;
+    optimizer.run();
+    return Ok(optimizer.finish());
+}
+
+/// Like `minimize`, but `f` evaluates a whole population at once: it gets the members row-major
+/// (`bounds.num_dimensions()` coordinates each) and writes one fitness value per member
+pub fn minimize_batched<F: FnMut(&[f64], &mut [f64])>(f: F, bounds: &DeBounds, config: &DeConfig) -> Result<DeResult, DeError> {
+    let mut optimizer = DeOptimizer::new_batched(f, bounds, config)?;
+    optimizer.run();
+    return Ok(optimizer.finish());
+}
+
+/// Like `minimize`, subject to `num_inequality` constraints g(x) <= 0 and `num_equality` constraints h(x) = 0:
+/// `g` writes the g values followed by the h values of a vector, selection follows `config.constraint_handling`
+///
+/// The best found vector may still be infeasible, see `DeResult::is_feasible`
+pub fn minimize_constrained<F: FnMut(&[f64]) -> f64, G: FnMut(&[f64], &mut [f64])>(f: F, g: G, num_inequality: usize, num_equality: usize, bounds: &DeBounds, config: &DeConfig) -> Result<DeResult, DeError> {
+    let mut optimizer = DeOptimizer::new_constrained(f, g, num_inequality, num_equality, bounds, config)?;
+    optimizer.run();
+    return Ok(optimizer.finish());
+}
+
+/// Like `minimize`, calling `progress` after every generation, returning `ControlFlow::Break` stops the run cleanly
+/// with the stop reason `Cancelled`
+pub fn minimize_with_progress<F: FnMut(&[f64]) -> f64, P: FnMut(&DeProgress) -> ControlFlow<()>>(f: F, bounds: &DeBounds, config: &DeConfig, progress: P) -> Result<DeResult, DeError> {
+    let mut optimizer = DeOptimizer::new(f, bounds, config)?;
+    optimizer.set_progress(progress);
+    optimizer.run();
+    return Ok(optimizer.finish());
+}
+
+/// Like `minimize_parallel`, but with `island_config.island_count` populations evolving in their own threads
+/// that exchange their best members every `island_config.migration_interval` generations
+///
+/// Island 0 runs with `config.seed`, so a single island gives the result of `minimize`. The run ends once the stop conditions
+/// hold on one island, the result counts the evaluations of all islands
+pub fn minimize_islands<F: Fn(&[f64]) -> f64 + Sync>(f: F, bounds: &DeBounds, config: &DeConfig, island_config: &DeIslandConfig) -> Result<DeResult, DeError> {
+    let (mut target, mut user_data) = closure_target(DeObjective::Parallel(Box::new(f)), None, bounds)?;
+    let mut config = *config;
+    let mut island_config = *island_config;
+    let mut raw = MaybeUninit::<DeRawResult>::uninit();
+    let p_user_data = &mut *user_data as *mut ClosureUserData as *mut c_void;
+    unsafe { de_island_minimum(&mut target, &mut config, &mut island_config, p_user_data, raw.as_mut_ptr()) }.to_result()?;
+    let result = unsafe { DeResult::from_raw(raw.assume_init()) };
+    if let Some(payload) = user_data.panic_payload.into_inner().unwrap() {
+        panic::resume_unwind(payload);
+    }
+    return Ok(result);
+}
+
+/// Minimizes the `num_objectives` objectives `f` writes for a vector at once with multi-objective differential evolution (GDE3),
+/// returning the non-dominated vectors of the final population instead of a single best one
+///
+/// Only the budget stop conditions (iterations, evaluations, seconds) apply, the search always uses DE/rand/1 without adaptation
+pub fn minimize_multi<F: FnMut(&[f64], &mut [f64])>(f: F, num_objectives: usize, bounds: &DeBounds, config: &DeConfig) -> Result<DeParetoFront, DeError> {
+    bounds.validate()?;
+    let mut user_data = MultiObjectiveUserData {
+        f: Box::new(f),
+        num_objectives,
+        panic_payload: None
+    };
+    let mut target = DeMultiOptimizationTarget {
+        f: Some(multi_objective_trampoline),
+        num_objectives: num_objectives as u32,
+        num_dimensions: bounds.num_dimensions() as u32,
+        left_bounds: bounds.left_bounds.as_ptr(),
+        right_bounds: bounds.right_bounds.as_ptr(),
+        variable_types: bounds.variable_types_ptr()
+    };
+    let mut config = *config;
+    let mut raw = MaybeUninit::<DeRawParetoFront>::uninit();
+    let p_user_data = &mut user_data as *mut MultiObjectiveUserData as *mut c_void;
+    unsafe { de_pareto_minimum(&mut target, &mut config, p_user_data, raw.as_mut_ptr()) }.to_result()?;
+    let front = unsafe { DeParetoFront::from_raw(raw.assume_init()) };
+    if let Some(payload) = user_data.panic_payload {
+        panic::resume_unwind(payload);
+    }
+    return Ok(front);
+}
+
+/// Niching differential evolution: minimizes `f` with crowding selection (whatever `config.selection` says) and returns the distinct
+/// optima of the final population, de-duplicated by `radius`, instead of a single best vector
+///
+/// Best-guided strategies pull the niches together, `DeMutationStrategy::Rand1` with a large population is the usual choice
+pub fn minimize_niching<F: FnMut(&[f64]) -> f64>(f: F, bounds: &DeBounds, config: &DeConfig, radius: f64) -> Result<DeOptima, DeError> {
+    let (mut target, mut user_data) = closure_target(DeObjective::Single(Box::new(f)), None, bounds)?;
+    let mut config = *config;
+    let mut raw = MaybeUninit::<DeRawOptima>::uninit();
+    let p_user_data = &mut *user_data as *mut ClosureUserData as *mut c_void;
+    unsafe { de_niching_minimum(&mut target, &mut config, radius, p_user_data, raw.as_mut_ptr()) }.to_result()?;
+    let optima = unsafe { DeOptima::from_raw(raw.assume_init()) };
+    if let Some(payload) = user_data.panic_payload.into_inner().unwrap() {
+        panic::resume_unwind(payload);
+    }
+    return Ok(optima);
+}
+
+#[cfg(test)]
+mod test {
+    use std::ops::ControlFlow;
+
+    use crate::de::{self, DeBoundaryHandling, DeBounds, DeConfig, DeConstraintHandling, DeOptimizer, DeAdaptation, DeError, DeInitialization, DeLimitation, DeMutationStrategy, DeStopCombination, DeStopCondition, DeStopReason, DeStopType, DeVariableType, DeIslandConfig, DeMigrationTopology};
+
+    fn sphere(x: &[f64]) -> f64 {
+        return x.iter().map(|a| a.powi(2)).sum();
+    }
+
+    #[test]
+    fn minimize_sphere_test() {
+        let config = DeConfig { seed: 7, record_history: true, ..Default::default() };
+        let result = de::minimize(sphere, &DeBounds::uniform(5, (-5.0, 5.0)), &config).unwrap();
+
+        assert_eq!(result.best.len(), 5);
+        assert!(result.best_fitness < 1e-2);
+        assert_eq!(result.best_fitness, sphere(&result.best));
+        assert_eq!(result.history.unwrap().len() as u64, result.generations);
+    }
+
+    #[test]
+    fn minimize_seed_test() {
+        let config = DeConfig { seed: 42, ..Default::default() };
+        let a = de::minimize(sphere, &DeBounds::uniform(3, (-1.0, 1.0)), &config).unwrap();
+        let b = de::minimize(sphere, &DeBounds::uniform(3, (-1.0, 1.0)), &config).unwrap();
+
+        assert_eq!(a.best, b.best);
+        assert_eq!(a.best_fitness, b.best_fitness);
+    }
+
+    #[test]
+    fn minimize_capturing_closure_test() {
+        let mut call_count = 0u64;
+        let result = de::minimize(|x| {
+            call_count += 1;
+            return sphere(x);
+        }, &DeBounds::uniform(2, (-1.0, 1.0)), &DeConfig::default()).unwrap();
+
+        assert_eq!(call_count, result.evaluations);
+    }
+
+    #[test]
+    fn minimize_validation_test() {
+        let bounds = DeBounds::uniform(2, (-1.0, 1.0));
+        let too_small = DeConfig { population_size: 3, mutation_strategy: DeMutationStrategy::Rand1, ..Default::default() };
+        let unbounded = DeConfig {
+            stop_condition: DeStopCondition { stype: DeStopType::StopWhenSatisfied, union: DeLimitation { accuracy: -1.0 } },
+            ..Default::default()
+        };
+
+        assert_eq!(de::minimize(sphere, &bounds, &too_small).unwrap_err(), DeError::InvalidPopulationSize);
+        assert_eq!(de::minimize(sphere, &bounds, &unbounded).unwrap_err(), DeError::InvalidStopCondition);
+        assert_eq!(de::minimize(sphere, &DeBounds::uniform(0, (-1.0, 1.0)), &DeConfig::default()).unwrap_err(), DeError::InvalidDimensions);
+        assert_eq!(de::minimize(sphere, &DeBounds::uniform(2, (1.0, -1.0)), &DeConfig::default()).unwrap_err(), DeError::InvalidBounds);
+        assert_eq!(de::minimize(sphere, &bounds, &DeConfig { crossover_probability: f64::NAN, ..Default::default() }).unwrap_err(), DeError::InvalidCrossoverProbability);
+    }
+
+    #[test]
+    fn minimize_combined_stop_conditions_test() {
+        let bounds = DeBounds::uniform(2, (-1.0, 1.0));
+        let mut capped = DeConfig { stop_condition: DeStopCondition::when_satisfied(-1.0), ..Default::default() };
+        capped.set_extra_stop_conditions(&[DeStopCondition::after_evaluations(500)], DeStopCombination::Any);
+        let result = de::minimize(sphere, &bounds, &capped).unwrap();
+
+        assert_eq!(result.stop_reason, DeStopReason::EvaluationsExhausted);
+        assert!(result.evaluations >= 500 && result.evaluations < 500 + 20);
+
+        let mut all = DeConfig { stop_condition: DeStopCondition::after_iters(5), max_generations: 10_000, ..Default::default() };
+        all.set_extra_stop_conditions(&[DeStopCondition::on_convergence(1e-9)], DeStopCombination::All);
+        let result = de::minimize(sphere, &bounds, &all).unwrap();
+
+        assert!(result.generations > 5);
+        assert!(result.best_fitness < 1e-9);
+    }
+
+    #[test]
+    fn minimize_per_dimension_bounds_test() {
+        let bounds = DeBounds::per_dimension(&[(1.0, 2.0), (-3.0, -2.0), (-1.0, 1.0)]);
+        let result = de::minimize(sphere, &bounds, &DeConfig { seed: 3, ..Default::default() }).unwrap();
+
+        for (j, coordinate) in result.best.iter().enumerate() {
+            assert!(*coordinate >= bounds.left_bounds[j] && *coordinate <= bounds.right_bounds[j]);
+        }
+        assert!((result.best[0] - 1.0).abs() < 1e-6);
+        assert!((result.best[1] + 2.0).abs() < 1e-6);
+
+        let mismatched = DeBounds { left_bounds: vec![0.0; 2], right_bounds: vec![1.0; 3], variable_types: Vec::new() };
+        assert_eq!(de::minimize(sphere, &mismatched, &DeConfig::default()).unwrap_err(), DeError::InvalidBounds);
+    }
+
+    #[test]
+    fn minimize_boundary_handling_test() {
+        // The optimum sits on the edge of the box, every rule has to keep the probes inside it
+        let edge = |x: &[f64]| x.iter().map(|a| (a - 1.0).powi(2)).sum::<f64>();
+        let bounds = DeBounds::uniform(4, (-1.0, 1.0));
+        for boundary_handling in [DeBoundaryHandling::Clamp, DeBoundaryHandling::Reflect, DeBoundaryHandling::Reinitialize, DeBoundaryHandling::Midpoint] {
+            let config = DeConfig { boundary_handling, amplification_factor: 2.0, seed: 11, ..Default::default() };
+            let result = de::minimize(|x| {
+                assert!(x.iter().all(|a| (-1.0..=1.0).contains(a)));
+                return edge(x);
+            }, &bounds, &config).unwrap();
+
+            assert!(result.best_fitness < edge(&[0.0; 4]));
+        }
+    }
+
+    #[test]
+    fn minimize_warm_start_test() {
+        let bounds = DeBounds::uniform(3, (-5.0, 5.0));
+        let optimum = [0.0, 0.0, 0.0];
+        let config = DeConfig { stop_condition: DeStopCondition::after_iters(0), seed: 5, ..Default::default() };
+        let result = de::minimize_warm_start(sphere, &bounds, &config, &[optimum]).unwrap();
+
+        assert_eq!(result.best_fitness, 0.0);
+        assert_eq!(de::minimize_warm_start(sphere, &bounds, &config, &[[0.0, 0.0]]).unwrap_err(), DeError::InvalidInitialPopulation);
+        assert_eq!(de::minimize_warm_start(sphere, &bounds, &config, &[[0.0, 0.0, 6.0]]).unwrap_err(), DeError::InvalidInitialPopulation);
+    }
+
+    #[test]
+    fn initialization_test() {
+        let bounds = DeBounds::uniform(2, (0.0, 10.0));
+        let config = DeConfig { population_size: 10, stop_condition: DeStopCondition::after_iters(0), seed: 9, ..Default::default() };
+        for initialization in [DeInitialization::Uniform, DeInitialization::LatinHypercube, DeInitialization::Halton, DeInitialization::Sobol, DeInitialization::Opposition] {
+            let mut evaluated = Vec::new();
+            let result = de::minimize(|x| { evaluated.push(x.to_vec()); sphere(x) }, &bounds, &DeConfig { initialization, ..config }).unwrap();
+
+            let initial_evaluations = result.evaluations - result.generations * 10;
+            assert!(evaluated.iter().flatten().all(|&x| (0.0..=10.0).contains(&x)));
+            if initialization == DeInitialization::Opposition {
+                assert_eq!(initial_evaluations, 20);
+                for (member, opposite) in evaluated[..10].iter().zip(&evaluated[10..]) {
+                    assert!((member[0] + opposite[0] - 10.0).abs() < 1e-12);
+                }
+            } else {
+                assert_eq!(initial_evaluations, 10);
+            }
+            if initialization == DeInitialization::LatinHypercube {
+                for j in 0..2 {
+                    let mut strata = evaluated[..10].iter().map(|x| x[j] as usize).collect::<Vec<_>>();
+                    strata.sort();
+                    assert_eq!(strata, (0..10).collect::<Vec<_>>());
+                }
+            }
+        }
+
+        let config = DeConfig { initialization: DeInitialization::Sobol, ..config };
+        // Up to the random shift, Sobol points 1 .. 15 of every coordinate are the distinct nonzero multiples of 1/16
+        let mut evaluated = Vec::new();
+        de::minimize(|x| { evaluated.push(x.to_vec()); sphere(x) }, &DeBounds::uniform(de::DE_SOBOL_MAX_DIMENSIONS, (0.0, 1.0)), &DeConfig { population_size: 15, ..config }).unwrap();
+        for j in 0..de::DE_SOBOL_MAX_DIMENSIONS {
+            let mut strata = evaluated[..15].iter().map(|x| ((x[j] - evaluated[0][j]).rem_euclid(1.0) * 16.0).round() as usize % 16).collect::<Vec<_>>();
+            strata.sort();
+            strata.dedup();
+            assert_eq!(strata.len(), 15, "coordinate {}", j);
+        }
+        assert_eq!(de::minimize(sphere, &DeBounds::uniform(de::DE_SOBOL_MAX_DIMENSIONS + 1, (0.0, 1.0)), &config).unwrap_err(), DeError::InvalidInitialization);
+    }
+
+    #[test]
+    fn adaptation_test() {
+        let bounds = DeBounds::uniform(10, (-5.0, 5.0));
+        for (adaptation, archive_rate) in [(DeAdaptation::Jade, 0.0), (DeAdaptation::Jade, 1.0), (DeAdaptation::Shade, 0.0), (DeAdaptation::Shade, 2.0), (DeAdaptation::Jde, 0.0)] {
+            let config = DeConfig {
+                population_size: 30,
+                mutation_strategy: DeMutationStrategy::CurrentToPBest1,
+                adaptation,
+                archive_rate,
+                stop_condition: DeStopCondition::after_iters(300),
+                seed: 13,
+                ..Default::default()
+            };
+            let result = de::minimize(sphere, &bounds, &config).unwrap();
+
+            assert!(result.best_fitness < 1e-6, "{:?} with archive rate {} reached {}", adaptation, archive_rate, result.best_fitness);
+        }
+
+        let config = DeConfig { adaptation: DeAdaptation::Shade, history_size: 0, ..Default::default() };
+        assert_eq!(de::minimize(sphere, &bounds, &config).unwrap_err(), DeError::InvalidAdaptation);
+        let config = DeConfig { adaptation: DeAdaptation::Jade, adaptation_rate: 0.0, ..Default::default() };
+        assert_eq!(de::minimize(sphere, &bounds, &config).unwrap_err(), DeError::InvalidAdaptation);
+        let config = DeConfig { adaptation: DeAdaptation::Jde, jde_tau2: 1.5, ..Default::default() };
+        assert_eq!(de::minimize(sphere, &bounds, &config).unwrap_err(), DeError::InvalidAdaptation);
+
+        // Only DE/current-to-pbest/1 reads the archive, other strategies give the same seeded result with or without it
+        let config = DeConfig { adaptation: DeAdaptation::Jade, mutation_strategy: DeMutationStrategy::Rand1, seed: 13, ..Default::default() };
+        let with_archive = de::minimize(sphere, &bounds, &DeConfig { archive_rate: 1.0, ..config }).unwrap();
+        assert_eq!(with_archive.best, de::minimize(sphere, &bounds, &config).unwrap().best);
+    }
+
+    #[test]
+    fn strategy_ensemble_test() {
+        let bounds = DeBounds::uniform(10, (-5.0, 5.0));
+        for adaptation in [DeAdaptation::Sade, DeAdaptation::Epsde] {
+            let config = DeConfig { population_size: 30, adaptation, learning_period: 20, stop_condition: DeStopCondition::after_iters(300), seed: 59, ..Default::default() };
+            let result = de::minimize(sphere, &bounds, &config).unwrap();
+
+            assert!(result.best_fitness < 1e-6, "{:?} reached {}", adaptation, result.best_fitness);
+            // Every trial of every generation is counted once, and only the default pool is used
+            assert_eq!(result.strategy_usage.iter().map(|usage| usage.trials).sum::<u64>(), result.generations * 30);
+            assert_eq!(result.strategy_usage.len(), 4);
+            assert!(result.strategy_usage.iter().all(|usage| usage.successes <= usage.trials && usage.strategy != DeMutationStrategy::RandBest1));
+        }
+
+        let mut config = DeConfig { population_size: 5, adaptation: DeAdaptation::Sade, stop_condition: DeStopCondition::after_iters(10), seed: 61, ..Default::default() };
+        config.set_strategy_pool(&[DeMutationStrategy::Rand1, DeMutationStrategy::CurrentToPBest1]);
+        let result = de::minimize(sphere, &bounds, &config).unwrap();
+        assert_eq!(result.strategy_usage.iter().map(|usage| usage.strategy).collect::<Vec<_>>(), [DeMutationStrategy::Rand1, DeMutationStrategy::CurrentToPBest1]);
+        // The selection probabilities belong to the pool the run started with
+        let mut optimizer = DeOptimizer::new(sphere, &bounds, &config).unwrap();
+        assert_eq!(optimizer.set_config(&DeConfig { strategy_pool: DeMutationStrategy::Rand1.pool_bit(), ..config }).unwrap_err(), DeError::InvalidConfigChange);
+        optimizer.set_config(&DeConfig { amplification_factor: 0.7, ..config }).unwrap();
+
+        // A fixed strategy is reported as the only one used
+        let result = de::minimize(sphere, &bounds, &DeConfig { adaptation: DeAdaptation::None, ..config }).unwrap();
+        assert_eq!(result.strategy_usage.len(), 1);
+        assert_eq!(result.strategy_usage[0].strategy, DeMutationStrategy::RandBest1);
+
+        config.set_strategy_pool(&[DeMutationStrategy::Rand2]);
+        assert_eq!(de::minimize(sphere, &bounds, &config).unwrap_err(), DeError::InvalidPopulationSize);
+        let config = DeConfig { strategy_pool: 1 << 7, ..config };
+        assert_eq!(de::minimize(sphere, &bounds, &config).unwrap_err(), DeError::InvalidAdaptation);
+        let config = DeConfig { strategy_pool: 0, learning_period: 0, ..config };
+        assert_eq!(de::minimize(sphere, &bounds, &config).unwrap_err(), DeError::InvalidAdaptation);
+    }
+
+    #[test]
+    fn population_reduction_test() {
+        let bounds = DeBounds::uniform(10, (-5.0, 5.0));
+        let config = DeConfig {
+            population_size: 60,
+            final_population_size: 4,
+            reduction_evaluations: 20_000,
+            mutation_strategy: DeMutationStrategy::CurrentToPBest1,
+            adaptation: DeAdaptation::Shade,
+            archive_rate: 1.4,
+            stop_condition: DeStopCondition::after_evaluations(20_000),
+            seed: 17,
+            record_history: true,
+            ..Default::default()
+        };
+        let result = de::minimize(sphere, &bounds, &config).unwrap();
+
+        assert!(result.best_fitness < 1e-8, "reached {}", result.best_fitness);
+        // The shrinking population makes the generations cheaper, so more of them fit into the budget than with 60 members
+        assert!(result.generations > 20_000 / 60);
+        assert!(result.evaluations < 20_000 + 60);
+
+        let config = DeConfig { final_population_size: 2, ..config };
+        assert_eq!(de::minimize(sphere, &bounds, &config).unwrap_err(), DeError::InvalidPopulationSize);
+        let config = DeConfig { final_population_size: 10, reduction_evaluations: 0, ..config };
+        assert_eq!(de::minimize(sphere, &bounds, &config).unwrap_err(), DeError::InvalidPopulationSize);
+    }
+
+    #[test]
+    fn optimizer_test() {
+        let bounds = DeBounds::uniform(4, (-5.0, 5.0));
+        let config = DeConfig { population_size: 15, stop_condition: DeStopCondition::after_iters(50), seed: 19, ..Default::default() };
+        let mut optimizer = DeOptimizer::new(sphere, &bounds, &config).unwrap();
+        for _ in 0..50 {
+            assert!(!optimizer.step());
+        }
+        assert!(optimizer.step());
+        let population = optimizer.population();
+        assert_eq!(population.len(), 15);
+        assert!(population.iter().all(|member| member.fitness == sphere(&member.coordinates)));
+        let result = optimizer.finish();
+
+        // Stepping through a run gives the same result as running it at once
+        let expected = de::minimize(sphere, &bounds, &config).unwrap();
+        assert_eq!(result.best, expected.best);
+        assert_eq!(result.generations, 51);
+        assert_eq!(result.stop_reason, DeStopReason::ItersExhausted);
+
+        let mut optimizer = DeOptimizer::new(sphere, &bounds, &config).unwrap();
+        optimizer.step();
+        optimizer.set_config(&DeConfig { amplification_factor: 0.8, mutation_strategy: DeMutationStrategy::Rand1, ..config }).unwrap();
+        assert_eq!(optimizer.set_config(&DeConfig { population_size: 16, ..config }).unwrap_err(), DeError::InvalidConfigChange);
+        assert_eq!(optimizer.config().amplification_factor, 0.8);
+        optimizer.step();
+        assert_eq!(optimizer.generations(), 2);
+        assert_eq!(optimizer.evaluations(), 3 * 15);
+        assert!(optimizer.best().fitness <= optimizer.population().iter().map(|member| member.fitness).fold(f64::INFINITY, f64::min));
+        assert_eq!(optimizer.finish().stop_reason, DeStopReason::Cancelled);
+    }
+
+    #[test]
+    #[should_panic(expected = "objective failed")]
+    fn optimizer_panic_test() {
+        let mut call_count = 0;
+        let mut optimizer = DeOptimizer::new(|x| {
+            call_count += 1;
+            if call_count > 30 {
+                panic!("objective failed");
+            }
+            return sphere(x);
+        }, &DeBounds::uniform(2, (-1.0, 1.0)), &DeConfig::default()).unwrap();
+        optimizer.run();
+    }
+
+    #[test]
+    fn progress_test() {
+        let bounds = DeBounds::uniform(3, (-5.0, 5.0));
+        let config = DeConfig { stop_condition: DeStopCondition::after_iters(1000), seed: 23, ..Default::default() };
+        let mut best_fitnesses = Vec::new();
+        let result = de::minimize_with_progress(sphere, &bounds, &config, |progress| {
+            assert_eq!(progress.population_size(), 20);
+            assert_eq!(progress.fitness()[0], sphere(progress.member(0)));
+            best_fitnesses.push(progress.best_fitness);
+            return if progress.generation == 10 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) };
+        }).unwrap();
+
+        assert_eq!(result.generations, 10);
+        assert_eq!(result.stop_reason, DeStopReason::Cancelled);
+        assert_eq!(best_fitnesses.len(), 10);
+        assert_eq!(*best_fitnesses.last().unwrap(), result.best_fitness);
+        assert!(best_fitnesses.windows(2).all(|pair| pair[1] <= pair[0]));
+    }
+
+    #[test]
+    fn batched_test() {
+        let bounds = DeBounds::uniform(4, (-5.0, 5.0));
+        let config = DeConfig { population_size: 12, initialization: DeInitialization::Opposition, seed: 29, ..Default::default() };
+        let mut batch_sizes = Vec::new();
+        let result = de::minimize_batched(|population, fitness| {
+            batch_sizes.push(fitness.len());
+            for (member, value) in population.chunks(4).zip(fitness.iter_mut()) {
+                *value = sphere(member);
+            }
+        }, &bounds, &config).unwrap();
+
+        // The same run with a one-by-one objective
+        let expected = de::minimize(sphere, &bounds, &config).unwrap();
+        assert_eq!(result.best, expected.best);
+        assert_eq!(result.evaluations, expected.evaluations);
+        assert_eq!(batch_sizes.len() as u64, 2 + result.generations);
+        assert!(batch_sizes.iter().all(|&size| size == 12));
+    }
+
+    #[test]
+    fn parallel_test() {
+        let bounds = DeBounds::uniform(5, (-5.0, 5.0));
+        let config = DeConfig { population_size: 23, seed: 31, thread_count: 4, ..Default::default() };
+        let thread_ids = std::sync::Mutex::new(std::collections::HashSet::new());
+        let result = de::minimize_parallel(|x| {
+            thread_ids.lock().unwrap().insert(std::thread::current().id());
+            return sphere(x);
+        }, &bounds, &config).unwrap();
+
+        // Every member is evaluated by exactly one thread, so the run matches the single-threaded one
+        let expected = de::minimize(sphere, &bounds, &DeConfig { thread_count: 1, ..config }).unwrap();
+        assert_eq!(result.best, expected.best);
+        assert_eq!(result.evaluations, expected.evaluations);
+        assert!(thread_ids.lock().unwrap().len() > 1);
+        assert_eq!(de::minimize(sphere, &bounds, &config).unwrap_err(), DeError::InvalidThreadCount);
+    }
+
+    #[test]
+    fn constrained_test() {
+        // min x0^2 + x1^2 subject to x0 + x1 >= 1 and x0 = 2 x1, optimum at (2/3, 1/3)
+        let bounds = DeBounds::uniform(2, (-5.0, 5.0));
+        let constraints = |x: &[f64], values: &mut [f64]| {
+            values[0] = 1.0 - x[0] - x[1];
+            values[1] = x[0] - 2.0 * x[1];
+        };
+        for constraint_handling in [DeConstraintHandling::FeasibilityRules, DeConstraintHandling::Penalty, DeConstraintHandling::Epsilon] {
+            let config = DeConfig {
+                population_size: 30,
+                constraint_handling,
+                stop_condition: DeStopCondition::after_iters(600),
+                seed: 31,
+                ..Default::default()
+            };
+            let result = de::minimize_constrained(sphere, constraints, 1, 1, &bounds, &config).unwrap();
+            assert!(result.is_feasible, "{:?} ended with violation {}", constraint_handling, result.best_violation);
+            assert_eq!(result.best_violation, 0.0);
+            assert!((result.best_fitness - 5.0 / 9.0).abs() < 1e-3, "{:?} reached {}", constraint_handling, result.best_fitness);
+            assert!((result.best[0] - 2.0 / 3.0).abs() < 1e-2 && (result.best[1] - 1.0 / 3.0).abs() < 1e-2);
+        }
+
+        // An unsatisfiable constraint leaves the least violating vector
+        let config = DeConfig { seed: 37, ..Default::default() };
+        let result = de::minimize_constrained(sphere, |x, values| values[0] = 20.0 - x[0] - x[1], 1, 0, &bounds, &config).unwrap();
+        assert!(!result.is_feasible);
+        assert!((result.best_violation - 10.0).abs() < 1e-6);
+
+        let penalty_config = DeConfig { constraint_handling: DeConstraintHandling::Penalty, penalty_factor: 0.0, ..Default::default() };
+        assert_eq!(de::minimize_constrained(sphere, constraints, 1, 1, &bounds, &penalty_config).unwrap_err(), DeError::InvalidConstraintHandling);
+    }
+
+    #[test]
+    fn variable_types_test() {
+        // Continuous x0, integer x1 and categorical x2, only category 3 is good
+        let bounds = DeBounds::per_dimension(&[(-5.0, 5.0), (-5.5, 5.5), (0.0, 4.0)])
+            .with_variable_types(&[DeVariableType::Continuous, DeVariableType::Integer, DeVariableType::Categorical]);
+        let mixed = |x: &[f64]| (x[0] - 1.3).powi(2) + (x[1] - 2.6).powi(2) + if x[2] == 3.0 { 0.0 } else { 1.0 };
+        for (mutation_strategy, initialization) in [(DeMutationStrategy::RandBest1, DeInitialization::Uniform), (DeMutationStrategy::Rand1, DeInitialization::LatinHypercube), (DeMutationStrategy::CurrentToPBest1, DeInitialization::Opposition)] {
+            let config = DeConfig { mutation_strategy, initialization, seed: 41, ..Default::default() };
+            let mut optimizer = DeOptimizer::new(mixed, &bounds, &config).unwrap();
+            optimizer.run();
+            for member in optimizer.population() {
+                assert!((-5.0..=5.0).contains(&member.coordinates[1]) && member.coordinates[1].fract() == 0.0);
+                assert!([0.0, 1.0, 2.0, 3.0, 4.0].contains(&member.coordinates[2]));
+            }
+            let result = optimizer.finish();
+            assert_eq!(result.best[1..], [3.0, 3.0], "{:?} with {:?}", mutation_strategy, initialization);
+            assert!((result.best[0] - 1.3).abs() < 1e-3);
+        }
+
+        let empty_integer_range = DeBounds::uniform(2, (0.2, 0.8)).with_variable_types(&[DeVariableType::Continuous, DeVariableType::Integer]);
+        assert_eq!(de::minimize(sphere, &empty_integer_range, &DeConfig::default()).unwrap_err(), DeError::InvalidVariableTypes);
+        let too_few_types = DeBounds::uniform(2, (-1.0, 1.0)).with_variable_types(&[DeVariableType::Integer]);
+        assert_eq!(de::minimize(sphere, &too_few_types, &DeConfig::default()).unwrap_err(), DeError::InvalidVariableTypes);
+        let integer_bounds = DeBounds::uniform(2, (-1.0, 1.0)).with_variable_types(&[DeVariableType::Integer; 2]);
+        assert_eq!(de::minimize_warm_start(sphere, &integer_bounds, &DeConfig::default(), &[[0.5, 0.0]]).unwrap_err(), DeError::InvalidInitialPopulation);
+    }
+
+    #[test]
+    fn multi_objective_test() {
+        // Schaffer's problem: the front is x in [0, 2], trading x^2 against (x - 2)^2
+        let bounds = DeBounds::uniform(1, (-5.0, 5.0));
+        let schaffer = |x: &[f64], objectives: &mut [f64]| {
+            objectives[0] = x[0] * x[0];
+            objectives[1] = (x[0] - 2.0) * (x[0] - 2.0);
+        };
+        let config = DeConfig { population_size: 30, stop_condition: DeStopCondition::after_iters(100), seed: 43, ..Default::default() };
+        let front = de::minimize_multi(schaffer, 2, &bounds, &config).unwrap();
+        assert_eq!(front.generations, 101);
+        assert_eq!(front.evaluations, 30 * 102);
+        assert!(front.points.len() >= 25, "only {} points on the front", front.points.len());
+        assert!(front.points.iter().all(|point| point.coordinates[0] >= -1e-3 && point.coordinates[0] <= 2.0 + 1e-3));
+        assert!(front.points.windows(2).all(|pair| pair[0].objectives[0] <= pair[1].objectives[0] && pair[0].objectives[1] >= pair[1].objectives[1]));
+        // Crowding keeps the ends of the front
+        assert!(front.points.first().unwrap().objectives[0] < 1e-3 && front.points.last().unwrap().objectives[1] < 1e-3);
+
+        let stagnation_config = DeConfig { stop_condition: DeStopCondition::on_stagnation(10), max_generations: 100, ..config };
+        assert_eq!(de::minimize_multi(schaffer, 2, &bounds, &stagnation_config).unwrap_err(), DeError::InvalidStopCondition);
+        assert_eq!(de::minimize_multi(schaffer, 0, &bounds, &config).unwrap_err(), DeError::InvalidDimensions);
+    }
+
+    #[test]
+    fn islands_test() {
+        let bounds = DeBounds::uniform(3, (-5.0, 5.0));
+        let config = DeConfig { seed: 47, ..Default::default() };
+
+        // A single island is a plain run
+        let single = DeIslandConfig { island_count: 1, ..Default::default() };
+        let result = de::minimize_islands(sphere, &bounds, &config, &single).unwrap();
+        let expected = de::minimize(sphere, &bounds, &config).unwrap();
+        assert_eq!(result.best, expected.best);
+        assert_eq!(result.evaluations, expected.evaluations);
+
+        let rastrigin = |x: &[f64]| 10.0 * x.len() as f64 + x.iter().map(|x| x * x - 10.0 * (2.0 * std::f64::consts::PI * x).cos()).sum::<f64>();
+        let bounds = DeBounds::uniform(6, (-5.12, 5.12));
+        let config = DeConfig { population_size: 30, mutation_strategy: DeMutationStrategy::Rand1, stop_condition: DeStopCondition::after_iters(400), seed: 53, ..Default::default() };
+        for topology in [DeMigrationTopology::Ring, DeMigrationTopology::FullyConnected] {
+            let island_config = DeIslandConfig { island_count: 4, migration_interval: 25, migration_count: 2, topology };
+            let result = de::minimize_islands(rastrigin, &bounds, &config, &island_config).unwrap();
+            assert_eq!(result.generations, 401);
+            assert_eq!(result.evaluations, 4 * 30 * 402);
+            assert_eq!(result.stop_reason, DeStopReason::ItersExhausted);
+            assert!(result.best_fitness < 1e-6, "{:?} reached {}", topology, result.best_fitness);
+        }
+
+        let no_islands = DeIslandConfig { island_count: 0, ..Default::default() };
+        assert_eq!(de::minimize_islands(sphere, &bounds, &config, &no_islands).unwrap_err(), DeError::InvalidIslandConfig);
+        let flooded = DeIslandConfig { island_count: 4, migration_count: 10, topology: DeMigrationTopology::FullyConnected, ..Default::default() };
+        assert_eq!(de::minimize_islands(sphere, &bounds, &config, &flooded).unwrap_err(), DeError::InvalidIslandConfig);
+        let threaded = DeConfig { thread_count: 2, ..config };
+        assert_eq!(de::minimize_islands(sphere, &bounds, &threaded, &DeIslandConfig::default()).unwrap_err(), DeError::InvalidThreadCount);
+    }
+
+    #[test]
+    fn niching_test() {
+        // Himmelblau's function has four global minima of value 0, at least 2.5 apart
+        let himmelblau = |x: &[f64]| (x[0] * x[0] + x[1] - 11.0).powi(2) + (x[0] + x[1] * x[1] - 7.0).powi(2);
+        let bounds = DeBounds::uniform(2, (-5.0, 5.0));
+        let config = DeConfig { population_size: 60, mutation_strategy: DeMutationStrategy::Rand1, stop_condition: DeStopCondition::after_iters(300), seed: 67, ..Default::default() };
+        let result = de::minimize_niching(himmelblau, &bounds, &config, 0.5).unwrap();
+
+        let minima = result.optima.iter().filter(|optimum| optimum.fitness < 1e-2).collect::<Vec<_>>();
+        assert_eq!(minima.len(), 4, "found {:?}", result.optima);
+        assert!(result.optima.windows(2).all(|pair| pair[0].fitness <= pair[1].fitness));
+        for (k, a) in result.optima.iter().enumerate() {
+            assert_eq!(a.fitness, himmelblau(&a.coordinates));
+            for b in &result.optima[k + 1..] {
+                assert!(a.coordinates.iter().zip(&b.coordinates).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt() > 0.5);
+            }
+        }
+        assert_eq!(result.generations, 301);
+        assert_eq!(result.stop_reason, DeStopReason::ItersExhausted);
+
+        // Parent selection converges to a single minimum
+        let mut optimizer = DeOptimizer::new(himmelblau, &bounds, &config).unwrap();
+        optimizer.run();
+        assert_eq!(optimizer.distinct_optima(0.5).unwrap().optima.iter().filter(|optimum| optimum.fitness < 1e-2).count(), 1);
+        assert_eq!(optimizer.distinct_optima(f64::NAN).unwrap_err(), DeError::InvalidNicheRadius);
+
+        assert_eq!(de::minimize_niching(himmelblau, &bounds, &config, -1.0).unwrap_err(), DeError::InvalidNicheRadius);
+    }
+}
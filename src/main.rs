@@ -3,12 +3,12 @@
 
 const FN_SIZE: usize = 30;
 
-use std::ops::AddAssign;
-use std::ptr;
+use std::ops::{AddAssign, ControlFlow};
 
 use clap::{Parser, Subcommand};
 use particle_swarm::pso_de::WorldState;
 use particle_swarm::de;
+use particle_swarm::vector::VectorN;
 
 #[derive(Parser, Clone, Debug)]
 struct Config {
@@ -16,16 +16,94 @@ struct Config {
 	functions: Vec<String>,
 	#[arg(long = "crossover")]
 	crossover_possibility: f64,
+	#[arg(long = "crossover-type", value_enum, default_value_t = de::DeCrossoverType::Binomial)]
+	crossover_type: de::DeCrossoverType,
+	#[arg(long = "boundary", value_enum, default_value_t = de::DeBoundaryHandling::Clamp)]
+	boundary_handling: de::DeBoundaryHandling,
+	/// How DE places its initial population
+	#[arg(long = "init", value_enum, default_value_t = de::DeInitialization::Uniform)]
+	initialization: de::DeInitialization,
+	/// Adapt F and CR online, --amplifier and --crossover then only give the starting values
+	#[arg(long, value_enum, default_value_t = de::DeAdaptation::None)]
+	adaptation: de::DeAdaptation,
+	/// SHADE memory slots
+	#[arg(long = "history-size", default_value_t = 6)]
+	history_size: u32,
+	/// External archive capacity in multiples of the population size (DE/current-to-pbest/1 only)
+	#[arg(long = "archive-rate", default_value_t = 0.0)]
+	archive_rate: f64,
+	/// Mutation strategies the sade and epsde adaptations choose from, defaults to rand-1,rand-2,best-2,current-to-best-1
+	#[arg(long = "strategy-pool", value_enum, value_delimiter = ',', num_args = 1..)]
+	strategy_pool: Vec<de::DeMutationStrategy>,
+	/// Generations the sade and epsde adaptations learn the strategy success rates over
+	#[arg(long = "learning-period", default_value_t = 50)]
+	learning_period: u32,
+	/// Let DE trials compete with their parent or with their nearest member
+	#[arg(long, value_enum, default_value_t = de::DeSelection::Parent)]
+	selection: de::DeSelection,
+	/// Run niching DE (crowding selection) and print the distinct optima at least this far apart (plain single DE runs only)
+	#[arg(long = "niche-radius")]
+	niche_radius: Option<f64>,
 	#[arg(long = "amplifier")]
 	diff_weight: f64,
 	#[arg(long = "diff-pop")]
 	diff_population: usize,
+	/// Shrink the DE population linearly down to this size (L-SHADE), needs --reduction-evals or --max-evals
+	#[arg(long = "final-pop")]
+	final_population: Option<u32>,
+	/// Evaluation budget of the population reduction, defaults to --max-evals
+	#[arg(long = "reduction-evals")]
+	reduction_evaluations: Option<u64>,
 	#[arg(long = "diff-iters")]
 	differential_iterations: usize,
 	#[arg(long = "lambda")]
 	lambda: f64,
+	#[arg(long, value_enum, default_value_t = de::DeMutationStrategy::RandBest1)]
+	strategy: de::DeMutationStrategy,
+	/// Fraction of the population DE/current-to-pbest/1 picks x_pbest from
+	#[arg(long = "p-best", default_value_t = 0.1)]
+	p_best_fraction: f64,
+	/// Print the best DE fitness every this many generations (single runs only)
+	#[arg(long = "progress", value_parser = clap::value_parser!(u64).range(1..))]
+	progress_interval: Option<u64>,
+	/// Threads evaluating each DE population
+	#[arg(long = "threads", default_value_t = 1)]
+	thread_count: u32,
+	/// Evolve this many DE populations in their own threads that exchange their best members (plain DE runs only)
+	#[arg(long, default_value_t = 1)]
+	islands: u32,
+	/// Generations between island migrations
+	#[arg(long = "migration-interval", default_value_t = 20)]
+	migration_interval: u32,
+	/// Best members an island sends to each of its neighbours
+	#[arg(long = "migration-count", default_value_t = 1)]
+	migration_count: u32,
+	/// Which islands exchange members
+	#[arg(long, value_enum, default_value_t = de::DeMigrationTopology::Ring)]
+	topology: de::DeMigrationTopology,
 	#[arg(long = "try-count")]
 	try_count: Option<usize>,
+	/// Base seed for the DE (and diff-part PSO) random streams, every thread/try derives its own stream from it
+	#[arg(long)]
+	seed: Option<u64>,
+	/// Additionally stop DE after this many function evaluations
+	#[arg(long = "max-evals")]
+	max_evaluations: Option<u64>,
+	/// Additionally stop DE after this many generations without improvement
+	#[arg(long = "stagnation")]
+	stagnation_iters: Option<u64>,
+	/// Additionally stop DE once the population fitness spread is at most this
+	#[arg(long = "spread")]
+	fitness_spread: Option<f64>,
+	/// Additionally stop DE after this many seconds
+	#[arg(long = "time-limit")]
+	time_limit: Option<f64>,
+	/// Stop DE only once all stop conditions hold instead of any
+	#[arg(long = "stop-when-all")]
+	stop_when_all: bool,
+	/// Hard cap on DE generations on top of the stop conditions
+	#[arg(long = "max-generations", default_value_t = 0)]
+	max_generations: u64,
 	#[command(subcommand)]
 	command: Option<ComputationMode>,
 }
@@ -44,6 +122,13 @@ enum ComputationMode {
 		#[arg(long)]
 		inertia_coefficient: f64
 	},
+	/// Trade the PSO result against its evaluation cost: multi-objective DE over particle count, iterations and coefficients
+	ParetoPart {
+		#[arg(long = "max-particles")]
+		max_particles: usize,
+		#[arg(long = "max-part-iters")]
+		max_particle_iterations: usize
+	},
 }
 
 struct BatchRunData {
@@ -94,14 +179,85 @@ impl AddAssign<f64> for BatchRunData {
 	}
 }
 
+fn create_de_config(config: &Config) -> de::DeConfig {
+	let mut de_config = de::DeConfig {
+		population_size: config.diff_population as u32,
+		final_population_size: config.final_population.unwrap_or(0),
+		reduction_evaluations: config.reduction_evaluations.or(config.max_evaluations).unwrap_or(0),
+		crossover_probability: config.crossover_possibility,
+		amplification_factor: config.diff_weight,
+		lambda: 0.5,
+		mutation_strategy: config.strategy,
+		p_best_fraction: config.p_best_fraction,
+		crossover_type: config.crossover_type,
+		boundary_handling: config.boundary_handling,
+		initialization: config.initialization,
+		adaptation: config.adaptation,
+		history_size: config.history_size,
+		archive_rate: config.archive_rate,
+		learning_period: config.learning_period,
+		selection: config.selection,
+		stop_condition: de::DeStopCondition::after_iters(config.differential_iterations as u64),
+		max_generations: config.max_generations,
+		thread_count: config.thread_count,
+		seed: config.seed.map_or(0, |base_seed| de::seed_for_stream(base_seed, 0)),
+		..Default::default()
+	};
+
+	de_config.set_strategy_pool(&config.strategy_pool);
+
+	let mut extra_stop_conditions = Vec::new();
+	if let Some(evaluations) = config.max_evaluations {
+		extra_stop_conditions.push(de::DeStopCondition::after_evaluations(evaluations));
+	}
+	if let Some(stagnation_iters) = config.stagnation_iters {
+		extra_stop_conditions.push(de::DeStopCondition::on_stagnation(stagnation_iters));
+	}
+	if let Some(fitness_spread) = config.fitness_spread {
+		extra_stop_conditions.push(de::DeStopCondition::on_convergence(fitness_spread));
+	}
+	if let Some(seconds) = config.time_limit {
+		extra_stop_conditions.push(de::DeStopCondition::after_seconds(seconds));
+	}
+	let combination = if config.stop_when_all { de::DeStopCombination::All } else { de::DeStopCombination::Any };
+	de_config.set_extra_stop_conditions(&extra_stop_conditions, combination);
+
+	return de_config;
+}
+
+fn create_island_config(config: &Config) -> de::DeIslandConfig {
+	return de::DeIslandConfig {
+		island_count: config.islands,
+		migration_interval: config.migration_interval,
+		migration_count: config.migration_count,
+		topology: config.topology
+	};
+}
+
+/// Applies the DE options `WorldState::new` does not take, `strategy_pool` is the bit set of `--strategy-pool`
+fn configure_world_de(world: &mut WorldState<FN_SIZE>, config: &Config, strategy_pool: u32) {
+	world.de_mutation_strategy = config.strategy;
+	world.de_p_best_fraction = config.p_best_fraction;
+	world.de_crossover_type = config.crossover_type;
+	world.de_boundary_handling = config.boundary_handling;
+	world.de_initialization = config.initialization;
+	world.de_adaptation = config.adaptation;
+	world.de_archive_rate = config.archive_rate;
+	world.de_strategy_pool = strategy_pool;
+}
+
 fn main() {
 	let builtin_fns = particle_swarm::functions::create_function_list::<FN_SIZE>();
 	let config = Config::parse();
 	if config.functions.is_empty() {
 		panic!("No functions given");
 	}
-	let test_functions = config.functions.into_iter().map(|s| {
-		return (s.clone(), builtin_fns.get(&s).unwrap());
+	if config.try_count.is_some() && matches!(config.command, Some(ComputationMode::ParetoPart { .. })) {
+		panic!("pareto-part runs once, --try-count is not supported");
+	}
+	let strategy_pool = create_de_config(&config).strategy_pool;
+	let test_functions = config.functions.iter().map(|s| {
+		return (s.clone(), builtin_fns.get(s).unwrap());
 	}).collect::<Vec<_>>();
 
 
@@ -109,52 +265,49 @@ fn main() {
 		for (function_name, function) in test_functions {
 			let bounds = function.get_bounds();
 			let func = function.get_function();
-			let c_func = function.get_c_function();
 			let tries_per_thread = tries.div_ceil(num_cpus::get());
 			let mut threads = Vec::with_capacity(num_cpus::get());
 			
 			match config.command {
 				Some(ComputationMode::DiffPart { particles, particle_iterations, social_coefficient, cognitive_coefficient, inertia_coefficient }) => {
-					let world = WorldState::new(particles, func, bounds, social_coefficient, cognitive_coefficient, inertia_coefficient, config.diff_population, config.crossover_possibility, config.diff_weight, config.lambda, config.differential_iterations);
-					for _ in 0..num_cpus::get() {
-						let mut thread_world = world.clone();
+					let mut world = WorldState::new(particles, func, bounds, social_coefficient, cognitive_coefficient, inertia_coefficient, config.diff_population, config.crossover_possibility, config.diff_weight, config.lambda, config.differential_iterations, 0); // every try reseeds it
+					configure_world_de(&mut world, &config, strategy_pool);
+					let base_seed = config.seed;
+					for thread_index in 0..num_cpus::get() {
+						let thread_world = world.clone();
 						threads.push(std::thread::spawn(move || {
 							let mut run_stats = BatchRunData::new();
-							for _ in 0..tries_per_thread {
-								thread_world.do_all_iterations(particle_iterations);
-								run_stats += func(thread_world.best_solution);
-								thread_world.reset();
+							for try_index in 0..tries_per_thread {
+								let mut try_world = thread_world.clone();
+								try_world.reseed(base_seed.map_or(0, |base_seed| de::seed_for_stream(base_seed, (thread_index * tries_per_thread + try_index) as u64)));
+								try_world.do_all_iterations(particle_iterations);
+								run_stats += func(try_world.best_solution);
 							}
 							return run_stats;
 						}));
 					}
 				}
+				Some(ComputationMode::ParetoPart { .. }) => unreachable!("rejected above"),
 				None => {
-					let stop_condition = de::DeStopCondition {
-						stype: de::DeStopType::StopAfterIters,
-						union: de::DeLimitation { iters: config.differential_iterations as u64 }
-					};
-					let mut config = de::DeConfig {
-						population_size: config.diff_population as u32,
-						crossover_probability: config.crossover_possibility,
-						amplification_factor: config.diff_weight,
-						lambda: 0.5,
-						stop_condition: stop_condition
-					};
-
-					let mut target = de::DeOptimizationTarget {
-						f: c_func,
-						num_dimensions: 30,
-						left_bound: -10.0,
-						right_bound: 10.0
-					};
-
-					for _ in 0..num_cpus::get() {
+					let base_seed = config.seed;
+					let mut de_config = create_de_config(&config);
+					let island_config = create_island_config(&config);
+
+					for thread_index in 0..num_cpus::get() {
 						threads.push(std::thread::spawn(move || {
 							let mut run_stats = BatchRunData::new();
-							for _ in 0..tries_per_thread {
-								let result = unsafe { de::de_minimum(&mut target, &mut config, ptr::null_mut()) };
-								unsafe { run_stats += c_func(result, ptr::null_mut()); }
+							for try_index in 0..tries_per_thread {
+								if let Some(base_seed) = base_seed {
+									de_config.seed = de::seed_for_stream(base_seed, (thread_index * tries_per_thread + try_index) as u64);
+								}
+								let objective = |x: &[f64]| func(VectorN::new(x.try_into().unwrap()));
+								let search_space = de::DeBounds::uniform(FN_SIZE, (-10.0, 10.0));
+								let result = if island_config.island_count > 1 {
+									de::minimize_islands(objective, &search_space, &de_config, &island_config)
+								} else {
+									de::minimize_parallel(objective, &search_space, &de_config)
+								}.expect("Invalid DE configuration");
+								run_stats += result.best_fitness;
 							}
 							return run_stats;
 						}));
@@ -172,52 +325,85 @@ fn main() {
 	} else {
 		let mut threads = Vec::new();
 		for (function_name, function) in test_functions {
-			let c_func = function.get_c_function();
 			let func = function.get_function();
 			let bounds = function.get_bounds();
 			match config.command {
 				Some(ComputationMode::DiffPart { particles, particle_iterations, social_coefficient, cognitive_coefficient, inertia_coefficient }) => {
+					let mut world = WorldState::new(particles, func, bounds, social_coefficient, cognitive_coefficient, inertia_coefficient, config.diff_population, config.crossover_possibility, config.diff_weight, config.lambda, config.differential_iterations, config.seed.map_or(0, |base_seed| de::seed_for_stream(base_seed, 0)));
+					configure_world_de(&mut world, &config, strategy_pool);
 					threads.push(std::thread::spawn(move || {
-						let mut world = WorldState::new(particles, func, bounds, social_coefficient, cognitive_coefficient, inertia_coefficient, config.diff_population, config.crossover_possibility, config.diff_weight, config.lambda, config.differential_iterations);
 						world.do_all_iterations(particle_iterations);
 						println!("{}: Found optimum at {:?} = {}", function_name, world.best_solution.coordinates, func(world.best_solution));
 					}));
 				}
 				None => {
+					let de_config = create_de_config(&config);
+					let island_config = create_island_config(&config);
 					threads.push(std::thread::spawn(move || {
-						let stop_condition = de::DeStopCondition {
-							stype: de::DeStopType::StopAfterIters,
-							union: de::DeLimitation { iters: config.differential_iterations as u64 }
-						};
-						let mut config = de::DeConfig {
-							population_size: config.diff_population as u32,
-							crossover_probability: config.crossover_possibility,
-							amplification_factor: config.diff_weight,
-							lambda: 0.5,
-							stop_condition: stop_condition
-						};
-						let mut target = de::DeOptimizationTarget {
-							f: c_func,
-							num_dimensions: 30,
-							left_bound: -10.0,
-							right_bound: 10.0
-						};
 						println!("Calling de_minimum");
-						let mut result = unsafe { de::de_minimum(&mut target, &mut config, ptr::null_mut()) };
+						let objective = |x: &[f64]| func(VectorN::new(x.try_into().unwrap()));
+						let search_space = de::DeBounds::uniform(FN_SIZE, (-10.0, 10.0));
+						if let Some(radius) = config.niche_radius {
+							let optima = de::minimize_niching(objective, &search_space, &de_config, radius).expect("Invalid DE configuration");
+							println!("{}: {} distinct optima after {} generations and {} evaluations", function_name, optima.optima.len(), optima.generations, optima.evaluations);
+							for optimum in optima.optima {
+								println!("{:?} = {}", optimum.coordinates, optimum.fitness);
+							}
+							return;
+						}
+						let result = if island_config.island_count > 1 {
+							de::minimize_islands(objective, &search_space, &de_config, &island_config).expect("Invalid DE configuration")
+						} else {
+							let mut optimizer = de::DeOptimizer::new_parallel(objective, &search_space, &de_config).expect("Invalid DE configuration");
+							if let Some(progress_interval) = config.progress_interval {
+								optimizer.set_progress(move |progress| {
+									if progress.generation % progress_interval == 0 {
+										println!("{}: generation {}, best value {}", function_name, progress.generation, progress.best_fitness);
+									}
+									return ControlFlow::Continue(());
+								});
+							}
+							optimizer.run();
+							optimizer.finish()
+						};
 						println!("de_minimum call returned.");
 						// Print the de_minimum result coordinates
-						for i in 0..result.num_dimensions as isize {
-							unsafe {
-								println!("Result coordinate {}: {}", i, *result.coordinates.offset(i));
-							}
+						for (i, coordinate) in result.best.iter().enumerate() {
+							println!("Result coordinate {}: {}", i, coordinate);
 						}
 
-						unsafe {
-							println!("Extreme function value: {}", c_func(result, ptr::null_mut()));
+						println!("Extreme function value: {}", result.best_fitness);
+						println!("Stopped after {} generations and {} evaluations ({:?})", result.generations, result.evaluations, result.stop_reason);
+						if result.strategy_usage.len() > 1 {
+							for usage in &result.strategy_usage {
+								println!("Strategy {:?}: {} of {} trials succeeded", usage.strategy, usage.successes, usage.trials);
+							}
+						}
+					}));
+				}
+				Some(ComputationMode::ParetoPart { max_particles, max_particle_iterations }) => {
+					let de_config = create_de_config(&config);
+					threads.push(std::thread::spawn(move || {
+						// particle count, iterations, social, cognitive, inertia
+						let search_space = de::DeBounds::per_dimension(&[(2.0, max_particles as f64), (1.0, max_particle_iterations as f64), (0.0, 2.5), (0.0, 2.5), (0.0, 1.0)])
+							.with_variable_types(&[de::DeVariableType::Integer, de::DeVariableType::Integer, de::DeVariableType::Continuous, de::DeVariableType::Continuous, de::DeVariableType::Continuous]);
+						let mut evaluation_index = 0;
+						let front = de::minimize_multi(|x, objectives| {
+							let (particles, iterations) = (x[0] as usize, x[1] as usize);
+							// Every evaluation gets its own stream, so seeded fronts replay bit-for-bit
+							let evaluation_seed = config.seed.map_or(0, |base_seed| de::seed_for_stream(base_seed, evaluation_index));
+							evaluation_index += 1;
+							let mut world = WorldState::new(particles, func, bounds, x[2], x[3], x[4], config.diff_population, config.crossover_possibility, config.diff_weight, config.lambda, config.differential_iterations, evaluation_seed);
+							for _ in 0..iterations {
+								world.do_plain_iteration();
+							}
+							objectives[0] = func(world.best_solution);
+							objectives[1] = (particles * (iterations + 1)) as f64; // function evaluations, including the initial ones
+						}, 2, &search_space, &de_config).expect("Invalid DE configuration");
+						println!("{}: Pareto front of {} points after {} generations", function_name, front.points.len(), front.generations);
+						for point in front.points {
+							println!("{} particles, {} iterations, coefficients {:?}: value {}, {} evaluations", point.coordinates[0], point.coordinates[1], &point.coordinates[2..], point.objectives[0], point.objectives[1]);
 						}
-						
-						// Free the result
-						unsafe { de::de_vector_free_coordinates(&mut result) };
 					}));
 				}
 			}
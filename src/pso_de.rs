@@ -30,16 +30,15 @@ impl<const N: usize> Particle<N> {
 
 pub unsafe extern "C" fn c_optimization_function_for_pso_control_params<const DIMENSIONS: usize>(input: de::Vector, user_data: *mut c_void) -> c_double {
 	let world_state = &mut *(user_data as *mut WorldState<DIMENSIONS>);
-	return optimization_function_for_pso_control_params(world_state, input);
+	let control_coeffs = std::slice::from_raw_parts(input.coordinates, input.num_dimensions as usize);
+	return optimization_function_for_pso_control_params(world_state, control_coeffs);
 }
 
-fn optimization_function_for_pso_control_params<const DIMENSIONS: usize>(ws: &mut WorldState<DIMENSIONS>, control_coeffs: de::Vector) -> f64 {
+fn optimization_function_for_pso_control_params<const DIMENSIONS: usize>(ws: &mut WorldState<DIMENSIONS>, control_coeffs: &[f64]) -> f64 {
 	// Calculate and return loss
-	unsafe {
-		ws.social_coefficient = *control_coeffs.coordinates.offset(0);
-		ws.cognitive_coefficient = *control_coeffs.coordinates.offset(1);
-		ws.inertia_coefficient = *control_coeffs.coordinates.offset(2);
-	}
+	ws.social_coefficient = control_coeffs[0];
+	ws.cognitive_coefficient = control_coeffs[1];
+	ws.inertia_coefficient = control_coeffs[2];
 	for particle in &mut ws.particles {
 		particle.social_coefficient = ws.social_coefficient;
 		particle.cognitive_coefficient = ws.cognitive_coefficient;
@@ -74,19 +73,34 @@ pub struct WorldState<const DIMENSIONS: usize> {
 	social_coefficient: f64,
 	cognitive_coefficient: f64,
 	inertia_coefficient: f64,
-	random_generator: fastrand::Rng,
+	random_generator: fastrand::Rng, // also seeds every DE tuning round, so a seeded world replays bit-for-bit
 
 	/* DE - specific */
 	pub de_population_size: usize,
 	pub de_crossover_probability: f64,
 	pub de_diff_weight: f64,
 	pub de_lambda: f64,
-	pub de_num_iters: usize
+	pub de_num_iters: usize,
+	pub de_mutation_strategy: de::DeMutationStrategy,
+	pub de_p_best_fraction: f64,
+	pub de_crossover_type: de::DeCrossoverType,
+	pub de_boundary_handling: de::DeBoundaryHandling,
+	pub de_initialization: de::DeInitialization,
+	pub de_adaptation: de::DeAdaptation,
+	pub de_archive_rate: f64,
+	pub de_strategy_pool: u32, // see `DeConfig::strategy_pool`, only used by the ensemble adaptations
+	pub de_coefficient_bounds: de::DeBounds, // (social, cognitive, inertia) search ranges
+	pub de_warm_start: bool // seed every DE tuning round with the current coefficients
+}
+
+fn create_random_generator(seed: u64) -> fastrand::Rng {
+	return if seed == 0 { fastrand::Rng::new() } else { fastrand::Rng::with_seed(seed) };
 }
 
 impl<const DIMENSIONS: usize> WorldState<DIMENSIONS> {
+	/// `seed` drives the particles and the DE tuning rounds (see `de::seed_for_stream` for per-thread streams), 0 picks a fresh non-reproducible one
 	pub fn new(particle_count: usize, function: fn(VectorN<DIMENSIONS>) -> f64, bounds: (f64, f64), social_coefficient: f64, cognitive_coefficient: f64, inertia_coefficient: f64, 
-			/*DE-specific */ de_population_size: usize, de_crossover_probability: f64, de_diff_weight: f64, de_lambda: f64, de_num_iters: usize) -> Self {
+			/*DE-specific */ de_population_size: usize, de_crossover_probability: f64, de_diff_weight: f64, de_lambda: f64, de_num_iters: usize, seed: u64) -> Self {
 		if bounds.0 >= bounds.1 {
 			panic!("Incorrect order of bounds or zero size");
 		}
@@ -100,14 +114,24 @@ impl<const DIMENSIONS: usize> WorldState<DIMENSIONS> {
 			social_coefficient,
 			cognitive_coefficient,
 			inertia_coefficient,
-			random_generator: fastrand::Rng::new(),
+			random_generator: create_random_generator(seed),
 
 			/* DE-specific */
 			de_population_size,
 			de_crossover_probability,
 			de_diff_weight,
 			de_lambda,
-			de_num_iters
+			de_num_iters,
+			de_mutation_strategy: de::DeMutationStrategy::RandBest1,
+			de_p_best_fraction: 0.1,
+			de_crossover_type: de::DeCrossoverType::Binomial,
+			de_boundary_handling: de::DeBoundaryHandling::Clamp,
+			de_initialization: de::DeInitialization::Uniform,
+			de_adaptation: de::DeAdaptation::None,
+			de_archive_rate: 0.0,
+			de_strategy_pool: 0,
+			de_coefficient_bounds: de::DeBounds::per_dimension(&[(0.0, 2.5), (0.0, 2.5), (0.0, 1.0)]),
+			de_warm_start: true
 		};
 
 		result.create_particles();
@@ -157,6 +181,14 @@ impl<const DIMENSIONS: usize> WorldState<DIMENSIONS> {
 		}
 	}
 
+	/// Starts over with fresh particles drawn from the random stream `seed` (0: non-reproducible), the coefficients are kept
+	pub fn reseed(&mut self, seed: u64) {
+		self.random_generator = create_random_generator(seed);
+		self.particles.clear();
+		self.best_solution_value = f64::INFINITY;
+		self.create_particles();
+	}
+
 	pub fn update_best_solutions(&mut self) {
 		for particle in &mut self.particles {
 			let particle_solution = (self.function)(particle.coordinates);
@@ -178,34 +210,37 @@ impl<const DIMENSIONS: usize> WorldState<DIMENSIONS> {
 			union: de::DeLimitation { iters: self.de_num_iters as u64 }
 		};
 
-		let mut de_config = de::DeConfig {
+		let de_config = de::DeConfig {
 			population_size: self.de_population_size as u32,
 			crossover_probability: self.de_crossover_probability,
 			amplification_factor: self.de_diff_weight,
 			lambda: self.de_lambda,
-			stop_condition: de_stop_condition
+			mutation_strategy: self.de_mutation_strategy,
+			p_best_fraction: self.de_p_best_fraction,
+			crossover_type: self.de_crossover_type,
+			boundary_handling: self.de_boundary_handling,
+			initialization: self.de_initialization,
+			adaptation: self.de_adaptation,
+			archive_rate: self.de_archive_rate,
+			strategy_pool: self.de_strategy_pool,
+			stop_condition: de_stop_condition,
+			seed: self.random_generator.u64(1..),
+			..Default::default()
 		};
 
-		let mut de_target = de::DeOptimizationTarget {
-			f: c_optimization_function_for_pso_control_params::<DIMENSIONS>,
-			num_dimensions: 3,
-			left_bound: 0.0,
-			right_bound: 1.0,
-		};
-
-		let mut de_manipulated_coeffs = unsafe { de::de_minimum(&mut de_target, &mut de_config, &mut self.clone() as *mut WorldState<DIMENSIONS> as *mut c_void) };
+		let mut rollout_world = self.clone();
+		let mut initial_population = Vec::new();
+		if self.de_warm_start {
+			let bounds = &self.de_coefficient_bounds;
+			let current_coeffs = [self.social_coefficient, self.cognitive_coefficient, self.inertia_coefficient];
+			initial_population.push((0..3).map(|j| current_coeffs[j].clamp(bounds.left_bounds[j], bounds.right_bounds[j])).collect::<Vec<_>>());
+		}
+		let de_result = de::minimize_warm_start(|control_coeffs| optimization_function_for_pso_control_params(&mut rollout_world, control_coeffs), &self.de_coefficient_bounds, &de_config, &initial_population).expect("Invalid DE configuration");
 		
 		// Update the coefficients
-		unsafe {
-			self.social_coefficient = *de_manipulated_coeffs.coordinates.offset(0);
-			self.cognitive_coefficient = *de_manipulated_coeffs.coordinates.offset(1);
-			self.inertia_coefficient = *de_manipulated_coeffs.coordinates.offset(2);
-		}
-
-		// Free the memory allocated inside de_manipulated_coeffs
-		unsafe {
-			de::de_vector_free_coordinates(&mut de_manipulated_coeffs);
-		}
+		self.social_coefficient = de_result.best[0];
+		self.cognitive_coefficient = de_result.best[1];
+		self.inertia_coefficient = de_result.best[2];
 
 		for particle in &mut self.particles {
 			particle.social_coefficient = self.social_coefficient;
@@ -223,6 +258,14 @@ impl<const DIMENSIONS: usize> WorldState<DIMENSIONS> {
 		self.update_best_solutions();
 	}
 
+	/// One PSO iteration with the current coefficients, without tuning them by DE
+	pub fn do_plain_iteration(&mut self) {
+		for particle in &mut self.particles {
+			particle.move_particle(self.best_solution, &mut self.random_generator);
+		}
+		self.update_best_solutions();
+	}
+
 	pub fn do_all_iterations(&mut self, iteration_count: usize) {
 		for _ in 0..iteration_count {
 			self.do_iteration();